@@ -1,26 +1,36 @@
-use std::{fs::File, io::Read};
+use std::{collections::HashMap, fs::File, io::Read};
 
 use docx_rs::{
-    BreakType, Docx, Header, IndentLevel, LineSpacing, Numbering, NumberingId, PageMargin, PageNum,
-    Paragraph, Pic, Run, RunFonts, RunProperty, Style, Table, TableCell, TableRow, VertAlignType,
+    AlignmentType, BreakType, Docx, FieldCharType, Header, Hyperlink, HyperlinkType, IndentLevel,
+    InstrText, LineSpacing, Numbering, NumberingId, PageMargin, PageNum, Paragraph, Pic, Run,
+    RunFonts, RunProperty, Style, Table, TableCell, TableRow, VertAlignType,
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::graph::{
     blocks::{
-        Block, BlockMacro, BlockMacroName, BreakVariant, LeafBlockName, ParentBlock,
+        Block, BlockMacro, BlockMacroName, BreakVariant, LeafBlock, LeafBlockName, ParentBlock,
         ParentBlockName, Section,
     },
-    inlines::{Inline, InlineSpanVariant},
+    inlines::{Inline, InlineSpan, InlineSpanVariant},
     lists::{DListItem, List, ListItem, ListVariant},
+    metadata::ElementMetadata,
 };
+use crate::highlight::{Syntax, TokenKind, syntax_for};
+use crate::stem::{Notation, omml::OMML_NAMESPACE, to_omml};
 
 use super::numbering::add_bullet_abstract_numbering;
 use super::styles::DocumentStyles;
 use super::units::{DXA_INCH, inches};
 
 static RE_WHITESPACE_NEWLINE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\s*\n"#).unwrap());
+static RE_EQUATION_PLACEHOLDER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        "<w:r>(?:<w:rPr\\s*/>|<w:rPr>.*?</w:rPr>)?<w:t[^>]*>\u{E000}(\\d+)\u{E001}</w:t></w:r>",
+    )
+    .unwrap()
+});
 
 pub fn asciidocr_default_docx() -> Docx {
     Docx::new()
@@ -65,6 +75,14 @@ pub struct DocxWriter {
     abstract_numbering: usize,
     numbering: usize,
     current_style: DocumentStyles,
+    labels: HashMap<String, String>,
+    bookmarks: usize,
+    /// OMML for any equations, which docx-rs can't add itself; see [`Self::insert_equations`]
+    equations: Vec<String>,
+    /// Whether to highlight source blocks, per the `:source-highlighter:` document attribute
+    source_highlighter: bool,
+    /// The alignment given by the current block's (or an enclosing block's) "text-*" role
+    alignment: Option<AlignmentType>,
 }
 
 impl Default for DocxWriter {
@@ -81,9 +99,68 @@ impl DocxWriter {
             abstract_numbering: 0,
             numbering: 0,
             current_style: DocumentStyles::Normal,
+            labels: HashMap::new(),
+            bookmarks: 0,
+            equations: vec![],
+            source_highlighter: false,
+            alignment: None,
         }
     }
 
+    /// Sets the built-in labels (e.g., admonition captions) used when rendering
+    pub fn with_labels(mut self, labels: HashMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Turns on syntax highlighting of source blocks
+    pub fn with_source_highlighter(mut self, source_highlighter: bool) -> Self {
+        self.source_highlighter = source_highlighter;
+        self
+    }
+
+    /// Creates a placeholder run for an equation, to be replaced by the OMML once the document
+    /// XML is built
+    fn equation_run(&mut self, source: &str, notation: Notation, display: bool) -> Run {
+        self.equations
+            .push(to_omml(source.trim(), notation, display));
+        Run::new().add_text(format!("\u{E000}{}\u{E001}", self.equations.len() - 1))
+    }
+
+    /// For STEM spans, the placeholder run for the (inline) equation
+    fn stem_run(&mut self, span: &InlineSpan) -> Option<Run> {
+        let notation = span.stem_notation()?;
+        let source: String = span
+            .inlines
+            .iter()
+            .map(|inline| inline.extract_values_to_string())
+            .collect();
+        Some(self.equation_run(&source, notation, false))
+    }
+
+    /// Replaces the equation placeholder runs in the built document XML with their OMML
+    pub fn insert_equations(&self, document: Vec<u8>) -> Vec<u8> {
+        if self.equations.is_empty() {
+            return document;
+        }
+        let xml = String::from_utf8_lossy(&document);
+        let xml = RE_EQUATION_PLACEHOLDER.replace_all(&xml, |captures: &regex::Captures| {
+            captures[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| self.equations.get(idx))
+                .cloned()
+                .unwrap_or_default()
+        });
+        // declare the math namespace on the document element
+        xml.replacen(
+            "<w:document ",
+            &format!("<w:document xmlns:m=\"{}\" ", OMML_NAMESPACE),
+            1,
+        )
+        .into_bytes()
+    }
+
     fn add_paragraph(&mut self, docx: Docx, mut para: Paragraph) -> Result<Docx, DocxRenderError> {
         if self.page_break_before {
             para = para.page_break_before(true);
@@ -92,14 +169,43 @@ impl DocxWriter {
 
         // set current style
         para = para.style(&self.current_style.style_id());
+        para = self.align(para);
 
         Ok(docx.add_paragraph(para))
     }
 
+    /// Aligns the paragraph according to any alignment role in effect
+    fn align(&self, para: Paragraph) -> Paragraph {
+        match self.alignment {
+            Some(alignment) => para.align(alignment),
+            None => para,
+        }
+    }
+
     pub fn add_block_to_doc(
         &mut self,
         mut docx: Docx,
         block: &Block,
+    ) -> Result<Docx, DocxRenderError> {
+        // alignment roles apply to the block and everything in it, as CSS classes would
+        let enclosing_alignment = self.alignment;
+        if let Some(alignment) = block.metadata().and_then(|meta| meta.text_alignment()) {
+            self.alignment = Some(match alignment {
+                "center" => AlignmentType::Center,
+                "right" => AlignmentType::Right,
+                "justify" => AlignmentType::Both,
+                _ => AlignmentType::Left,
+            });
+        }
+        docx = self.add_block_to_doc_aligned(docx, block)?;
+        self.alignment = enclosing_alignment;
+        Ok(docx)
+    }
+
+    fn add_block_to_doc_aligned(
+        &mut self,
+        mut docx: Docx,
+        block: &Block,
     ) -> Result<Docx, DocxRenderError> {
         match block {
             Block::Section(section) => docx = self.add_section(docx, section)?,
@@ -121,29 +227,14 @@ impl DocxWriter {
                         .add_paragraph(docx, Paragraph::new().add_run(Run::new().add_text("#")))?
                 }
             },
-            Block::LeafBlock(block) => {
-                match block.name {
-                    LeafBlockName::Verse => {
-                        docx = self.set_style(docx, DocumentStyles::Verse)?;
-                    }
-                    LeafBlockName::Listing | LeafBlockName::Literal => {
-                        docx = self.set_style(docx, DocumentStyles::Monospace)?;
-                    }
-                    _ => {}
-                }
-                let mut para = Paragraph::new();
-                para = self.add_inlines_to_para(para, block.inlines());
-                docx = self.add_paragraph(docx, para)?
-            }
+            Block::LeafBlock(block) => docx = self.add_leaf_block(docx, block)?,
             Block::ParentBlock(parent) => match parent.name {
-                ParentBlockName::Admonition => {
-                    docx = self.add_parent_block(docx, parent, "Admonition")?
-                }
+                ParentBlockName::Admonition => docx = self.add_admonition(docx, parent)?,
                 ParentBlockName::Example => {
-                    docx = self.add_parent_block(docx, parent, "Example")?
+                    docx = self.add_parent_block(docx, parent, DocumentStyles::Example)?
                 }
                 ParentBlockName::Sidebar => {
-                    docx = self.add_parent_block(docx, parent, "Sidebar")?
+                    docx = self.add_parent_block(docx, parent, DocumentStyles::Sidebar)?
                 }
                 ParentBlockName::Open => {
                     for child in parent.blocks.iter() {
@@ -164,6 +255,71 @@ impl DocxWriter {
         Ok(docx)
     }
 
+    fn add_leaf_block(
+        &mut self,
+        mut docx: Docx,
+        block: &LeafBlock,
+    ) -> Result<Docx, DocxRenderError> {
+        match block.name {
+            LeafBlockName::Verse => {
+                docx = self.set_style(docx, DocumentStyles::Verse)?;
+            }
+            LeafBlockName::Listing | LeafBlockName::Literal => {
+                docx = self.set_style(docx, DocumentStyles::Monospace)?;
+            }
+            _ => {}
+        }
+        let mut para = Paragraph::new();
+        para = match block.name {
+            LeafBlockName::Stem => para.add_run(self.stem_block_run(block)),
+            LeafBlockName::Listing => {
+                let syntax = self.syntax_for(block);
+                if syntax.is_some() || block.source_lines.is_some() {
+                    for run in source_runs(block, syntax) {
+                        para = para.add_run(run)
+                    }
+                    para
+                } else {
+                    self.add_inlines_to_para(para, block.inlines())
+                }
+            }
+            _ => self.add_inlines_to_para(para, block.inlines()),
+        };
+        docx = self.add_paragraph(docx, para)?;
+        if matches!(block.name, LeafBlockName::Verse) {
+            docx = self.add_attribution(docx, &block.metadata)?;
+        }
+        Ok(docx)
+    }
+
+    /// The syntax to highlight a source block with, if highlighting is on and the language known
+    fn syntax_for(&self, block: &LeafBlock) -> Option<&'static Syntax> {
+        if !self.source_highlighter {
+            return None;
+        }
+        block
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("language"))
+            .and_then(|language| syntax_for(language))
+    }
+
+    /// The placeholder run for a STEM block's (display) equation
+    fn stem_block_run(&mut self, block: &LeafBlock) -> Run {
+        let notation = block
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("notation"))
+            .and_then(|name| Notation::from_name(name))
+            .unwrap_or(Notation::AsciiMath);
+        let source: String = block
+            .inlines()
+            .iter()
+            .map(|inline| inline.extract_values_to_string())
+            .collect();
+        self.equation_run(&source, notation, true)
+    }
+
     fn add_list(&mut self, mut docx: Docx, list: &List) -> Result<Docx, DocxRenderError> {
         self.numbering += 1;
         match list.variant {
@@ -191,11 +347,26 @@ impl DocxWriter {
 
     fn add_list_item(&mut self, mut docx: Docx, item: &ListItem) -> Result<Docx, DocxRenderError> {
         // add principal with the correct variant match
-        let mut para = Paragraph::new();
-
-        para = para
-            .style(&self.current_style.style_id())
-            .numbering(NumberingId::new(self.numbering), IndentLevel::new(0));
+        let mut para = Paragraph::new().style(&self.current_style.style_id());
+        match item.callout_number() {
+            // callout list items get a badge, linked back to their marker, in place of a number
+            Some(number) => {
+                let badge = Run::new().add_text(format!("({}) ", number)).bold();
+                match (item.id(), item.callout_target()) {
+                    (Some(id), Some(target)) => {
+                        self.bookmarks += 1;
+                        para = para
+                            .add_bookmark_start(self.bookmarks, &id)
+                            .add_hyperlink(
+                                Hyperlink::new(target, HyperlinkType::Anchor).add_run(badge),
+                            )
+                            .add_bookmark_end(self.bookmarks);
+                    }
+                    _ => para = para.add_run(badge),
+                }
+            }
+            None => para = para.numbering(NumberingId::new(self.numbering), IndentLevel::new(0)),
+        }
         para = self.add_inlines_to_para(para, item.principal());
         docx = self.add_paragraph(docx, para)?;
         // add any children -- TODO style them as list continues
@@ -251,6 +422,9 @@ impl DocxWriter {
         if !section.title().is_empty() {
             docx = self.set_style(docx, DocumentStyles::Heading(section.level))?;
             let mut para = Paragraph::new();
+            if let Some(number) = &section.number {
+                para = para.add_run(Run::new().add_text(format!("{} ", number)));
+            }
             para = self.add_inlines_to_para(para, section.title());
             docx = self.add_paragraph(docx, para)?;
             // to ensure we go back
@@ -260,31 +434,87 @@ impl DocxWriter {
         for block in section.blocks.iter() {
             docx = self.add_block_to_doc(docx, block)?
         }
+        if section.style() == Some("index") {
+            docx = self.add_paragraph(docx, index_field_paragraph())?;
+        }
         Ok(docx)
     }
 
+    /// Examples and sidebars are (bordered) boxes, preceded by their caption, if any
     fn add_parent_block(
         &mut self,
         mut docx: Docx,
         parent: &ParentBlock,
-        name: &str,
+        style: DocumentStyles,
+    ) -> Result<Docx, DocxRenderError> {
+        docx = self.add_block_title(docx, &parent.title, &parent.caption_prefix)?;
+        docx = self.set_style(docx, style)?;
+        for child in parent.blocks.iter() {
+            docx = self.add_block_to_doc(docx, child)?
+        }
+        self.reset_style();
+        Ok(docx)
+    }
+
+    /// Adds a block title, with its caption prefix (e.g., "Example 1."), as a caption paragraph
+    fn add_block_title(
+        &mut self,
+        mut docx: Docx,
+        title: &[Inline],
+        caption_prefix: &Option<String>,
     ) -> Result<Docx, DocxRenderError> {
-        // admonitions
-        if let Some(variant) = &parent.variant {
-            docx = self.set_style(docx, DocumentStyles::SectionTitle(name.into()))?;
-            docx = self.add_paragraph(
-                docx,
-                Paragraph::new().add_run(Run::new().add_text(variant.to_string())),
-            )?
-        }
-        // examples and sidebars
+        if title.is_empty() {
+            return Ok(docx);
+        }
+        docx = self.set_style(docx, DocumentStyles::Caption)?;
+        let mut para = Paragraph::new();
+        if let Some(prefix) = caption_prefix {
+            para = para.add_run(Run::new().add_text(format!("{} ", prefix)));
+        }
+        para = self.add_inlines_to_para(para, title.to_vec());
+        docx = self.add_paragraph(docx, para)?;
+        self.reset_style();
+        Ok(docx)
+    }
+
+    /// Adds the "— Author, Citation" line of quotes and verses, if either is given
+    fn add_attribution(
+        &mut self,
+        docx: Docx,
+        metadata: &Option<ElementMetadata>,
+    ) -> Result<Docx, DocxRenderError> {
+        let Some(metadata) = metadata else {
+            return Ok(docx);
+        };
+        let credits: Vec<&str> = ["attribution", "citation"]
+            .iter()
+            .filter_map(|key| metadata.attributes.get(*key))
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+            .collect();
+        if credits.is_empty() {
+            return Ok(docx);
+        }
+        let para = Paragraph::new()
+            .line_spacing(LineSpacing::new().after(480))
+            .add_run(Run::new().add_text(format!("— {}", credits.join(", "))));
+        self.add_paragraph(docx, para)
+    }
+
+    /// Admonitions are shaded, bordered boxes, led by their label (e.g., "Note") and title, if any
+    fn add_admonition(
+        &mut self,
+        mut docx: Docx,
+        parent: &ParentBlock,
+    ) -> Result<Docx, DocxRenderError> {
+        docx = self.set_style(docx, DocumentStyles::Admonition)?;
+        let label = parent.admonition_label(&self.labels).unwrap_or_default();
+        let mut heading = Paragraph::new().add_run(Run::new().add_text(label).bold());
         if !parent.title.is_empty() {
-            docx = self.set_style(docx, DocumentStyles::SectionTitle(name.into()))?;
-            let mut title = Paragraph::new();
-            title = self.add_inlines_to_para(title, parent.title.clone());
-            docx = self.add_paragraph(docx, title)?;
+            heading = heading.add_run(Run::new().add_text(": ").bold());
+            heading = self.add_inlines_to_para(heading, parent.title.clone());
         }
-        docx = self.set_style(docx, DocumentStyles::SectionText(name.into()))?;
+        docx = self.add_paragraph(docx, heading)?;
         for child in parent.blocks.iter() {
             docx = self.add_block_to_doc(docx, child)?
         }
@@ -293,19 +523,12 @@ impl DocxWriter {
     }
 
     fn add_quote(&mut self, mut docx: Docx, parent: &ParentBlock) -> Result<Docx, DocxRenderError> {
+        docx = self.add_block_title(docx, &parent.title, &parent.caption_prefix)?;
         docx = self.set_style(docx, DocumentStyles::Quote)?;
         for child in parent.blocks.iter() {
             docx = self.add_block_to_doc(docx, child)?
         }
-        if let Some(metadata) = &parent.metadata {
-            if let Some(attribution) = metadata.attributes.get("attribution") {
-                let para = Paragraph::new()
-                    .line_spacing(LineSpacing::new().after(480))
-                    .add_run(Run::new().add_text(format!("— {}\n", attribution)));
-
-                docx = self.add_paragraph(docx, para)?
-            }
-        }
+        docx = self.add_attribution(docx, &parent.metadata)?;
         self.reset_style();
         Ok(docx)
     }
@@ -326,7 +549,7 @@ impl DocxWriter {
         let num_cells = table.blocks.len();
         let mut current_row: Vec<TableCell> = vec![];
         for (idx, block) in table.blocks.iter().enumerate() {
-            let mut para = Paragraph::new().style(&DocumentStyles::Table.style_id());
+            let mut para = self.align(Paragraph::new().style(&DocumentStyles::Table.style_id()));
             para = self.add_inlines_to_para(para, block.inlines());
             let cell = TableCell::new().add_paragraph(para);
             current_row.push(cell);
@@ -349,7 +572,24 @@ impl DocxWriter {
             let mut buf = vec![];
             let _ = img.read_to_end(&mut buf)?;
             let pic = Pic::new(&buf);
-            docx = docx.add_paragraph(Paragraph::new().add_run(Run::new().add_image(pic)));
+            docx =
+                docx.add_paragraph(self.align(Paragraph::new().add_run(Run::new().add_image(pic))));
+        } else if matches!(block.name, BlockMacroName::Audio | BlockMacroName::Video) {
+            // audio and video can't be embedded, so we add a (titled) link to them instead
+            let name = match block.name {
+                BlockMacroName::Audio => "Audio",
+                _ => "Video",
+            };
+            docx = self.add_block_title(docx, &block.caption, &None)?;
+            let url = block.media_url();
+            let para = Paragraph::new()
+                .add_run(Run::new().add_text(format!("{}: ", name)))
+                .add_hyperlink(
+                    Hyperlink::new(&url, HyperlinkType::External)
+                        .add_run(Run::new().add_text(&url)),
+                );
+            docx = self.add_paragraph(docx, para)?;
+            self.reset_style();
         } else {
             todo!()
         }
@@ -375,8 +615,38 @@ impl DocxWriter {
     /// Adds inlines to a given paragraph
     pub fn add_inlines_to_para(&mut self, mut para: Paragraph, inlines: Vec<Inline>) -> Paragraph {
         for inline in inlines.iter() {
-            for run in self.runs_from_inline(inline) {
-                para = para.add_run(run)
+            // inline anchors and spans with IDs become bookmarks
+            let bookmark = inline.id();
+            if let Some(name) = &bookmark {
+                self.bookmarks += 1;
+                para = para.add_bookmark_start(self.bookmarks, name);
+            }
+            match inline {
+                // links (including "mailto:" links) become hyperlinks
+                Inline::InlineRef(iref) if iref.is_link() => {
+                    let mut hyperlink = Hyperlink::new(&iref.target, HyperlinkType::External);
+                    for run in self.runs_from_inline(inline) {
+                        hyperlink = hyperlink.add_run(run)
+                    }
+                    para = para.add_hyperlink(hyperlink)
+                }
+                // code callout markers link to their callout list items
+                Inline::InlineSpan(span) if span.callout_target().is_some() => {
+                    let target = span.callout_target().unwrap().clone();
+                    let mut hyperlink = Hyperlink::new(&target, HyperlinkType::Anchor);
+                    for run in self.runs_from_inline(inline) {
+                        hyperlink = hyperlink.add_run(run)
+                    }
+                    para = para.add_hyperlink(hyperlink)
+                }
+                _ => {
+                    for run in self.runs_from_inline(inline) {
+                        para = para.add_run(run)
+                    }
+                }
+            }
+            if bookmark.is_some() {
+                para = para.add_bookmark_end(self.bookmarks);
             }
         }
         para
@@ -399,8 +669,16 @@ impl DocxWriter {
             }
             Inline::InlineSpan(span) => {
                 variants.push(&span.variant);
-                for inline in span.inlines.iter() {
-                    runs.extend(self.runs_from_inline_with_variant(inline, &mut variants))
+                if let Some(run) = self.stem_run(span) {
+                    runs.push(run)
+                } else if let Some(text) = span.ui_macro_text() {
+                    // keyboard, button, and menu macros become a single styled run
+                    runs.push(self.styled_run(text, &variants))
+                } else {
+                    for inline in span.inlines.iter() {
+                        runs.extend(self.runs_from_inline_with_variant(inline, &mut variants))
+                    }
+                    runs.extend(index_entry_field_runs(span));
                 }
             }
             Inline::InlineBreak(_) => {
@@ -418,6 +696,42 @@ impl DocxWriter {
         runs
     }
 
+    /// Creates a run from some text, styled according to the inline span variant(s) it's in
+    fn styled_run(&mut self, mut literal_text: String, variants: &[&InlineSpanVariant]) -> Run {
+        if self.line_break_before {
+            literal_text = literal_text.trim_start().into();
+            self.line_break_before = false;
+        }
+        let mut run = Run::new().add_text(literal_text);
+        for variant in variants {
+            match variant {
+                InlineSpanVariant::Strong | InlineSpanVariant::Button | InlineSpanVariant::Menu => {
+                    run = run.bold()
+                }
+                InlineSpanVariant::Emphasis => run = run.italic(),
+                InlineSpanVariant::Code | InlineSpanVariant::Keyboard => {
+                    run = run.fonts(RunFonts::new().ascii("Courier New"))
+                }
+                InlineSpanVariant::Mark => run = run.highlight("yellow"),
+                InlineSpanVariant::Stem => {} // handled as equations
+                InlineSpanVariant::IndexTerm => {}
+                InlineSpanVariant::Subscript => {
+                    run.run_property = RunProperty::new().vert_align(VertAlignType::SubScript)
+                }
+                InlineSpanVariant::Superscript => {
+                    run.run_property = RunProperty::new().vert_align(VertAlignType::SuperScript)
+                }
+                InlineSpanVariant::Footnote => {
+                    eprintln!(
+                        "Footnotes are not well supported; footnote text will be included in-line and higlighted for the time being."
+                    );
+                    run = run.highlight("blue")
+                }
+            }
+        }
+        run
+    }
+
     /// The version that allows for recursion, specifically nested inline spans
     fn runs_from_inline_with_variant<'a>(
         &mut self,
@@ -427,44 +741,21 @@ impl DocxWriter {
         let mut runs: Vec<Run> = Vec::new();
         match inline {
             Inline::InlineLiteral(lit) => {
-                let mut literal_text = lit.value_or_refd_char().replace("\n", " ");
-                if self.line_break_before {
-                    literal_text = literal_text.trim_start().into();
-                    self.line_break_before = false;
-                }
-                let mut run = Run::new().add_text(literal_text);
-                if !variants.is_empty() {
-                    for variant in variants {
-                        match variant {
-                            InlineSpanVariant::Strong => run = run.bold(),
-                            InlineSpanVariant::Emphasis => run = run.italic(),
-                            InlineSpanVariant::Code => {
-                                run = run.fonts(RunFonts::new().ascii("Courier New"))
-                            }
-                            InlineSpanVariant::Mark => run = run.highlight("yellow"),
-                            InlineSpanVariant::Subscript => {
-                                run.run_property =
-                                    RunProperty::new().vert_align(VertAlignType::SubScript)
-                            }
-                            InlineSpanVariant::Superscript => {
-                                run.run_property =
-                                    RunProperty::new().vert_align(VertAlignType::SuperScript)
-                            }
-                            InlineSpanVariant::Footnote => {
-                                eprintln!(
-                                    "Footnotes are not well supported; footnote text will be included in-line and higlighted for the time being."
-                                );
-                                run = run.highlight("blue")
-                            }
-                        }
-                    }
-                }
-                runs.push(run)
+                let literal_text = lit.value_or_refd_char().replace("\n", " ");
+                runs.push(self.styled_run(literal_text, variants))
             }
             Inline::InlineSpan(span) => {
                 variants.push(&span.variant);
-                for inline in span.inlines.iter() {
-                    runs.extend(self.runs_from_inline_with_variant(inline, variants))
+                if let Some(run) = self.stem_run(span) {
+                    runs.push(run)
+                } else if let Some(text) = span.ui_macro_text() {
+                    // keyboard, button, and menu macros become a single styled run
+                    runs.push(self.styled_run(text, variants))
+                } else {
+                    for inline in span.inlines.iter() {
+                        runs.extend(self.runs_from_inline_with_variant(inline, variants))
+                    }
+                    runs.extend(index_entry_field_runs(span));
                 }
             }
             Inline::InlineBreak(_) => {
@@ -482,3 +773,112 @@ impl DocxWriter {
         runs
     }
 }
+
+/// Creates the runs of a source block one line at a time: the (right-aligned) line number, if
+/// any, then the line itself, colored by syntax if given and highlighted if so designated
+fn source_runs(block: &LeafBlock, syntax: Option<&Syntax>) -> Vec<Run> {
+    let source = block.text_lines().join("\n");
+    let tokens = match syntax {
+        Some(syntax) => syntax.tokenize(&source),
+        None => vec![(TokenKind::Plain, source.as_str())],
+    };
+    // split the tokens into lines
+    let mut lines: Vec<Vec<(TokenKind, &str)>> = vec![vec![]];
+    for (kind, text) in tokens {
+        for (idx, part) in text.split('\n').enumerate() {
+            if idx > 0 {
+                lines.push(vec![]);
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().push((kind, part));
+            }
+        }
+    }
+
+    let start = block.source_lines.as_ref().map_or(1, |lines| lines.start);
+    let number_width = (start + lines.len()).saturating_sub(1).to_string().len();
+    let mut runs: Vec<Run> = vec![];
+    for (idx, line) in lines.iter().enumerate() {
+        let number = start + idx;
+        if idx > 0 {
+            runs.push(Run::new().add_break(BreakType::TextWrapping));
+        }
+        if block
+            .source_lines
+            .as_ref()
+            .is_some_and(|lines| lines.linenums)
+        {
+            runs.push(
+                Run::new()
+                    .add_text(format!("{:>width$}  ", number, width = number_width))
+                    .color("808080"),
+            );
+        }
+        let highlighted = block
+            .source_lines
+            .as_ref()
+            .is_some_and(|lines| lines.is_highlighted(number));
+        for (kind, text) in line {
+            let mut run = Run::new().add_text(*text);
+            if let Some(color) = token_color(kind) {
+                run = run.color(color);
+            }
+            if *kind == TokenKind::Keyword {
+                run = run.bold();
+            } else if *kind == TokenKind::Comment {
+                run = run.italic();
+            }
+            if highlighted {
+                run = run.highlight("yellow");
+            }
+            runs.push(run);
+        }
+    }
+    runs
+}
+
+/// The (hex) color of a highlighted source token
+fn token_color(kind: &TokenKind) -> Option<&'static str> {
+    match kind {
+        TokenKind::Plain => None,
+        TokenKind::Keyword => Some("0000FF"),
+        TokenKind::Type => Some("267F99"),
+        TokenKind::Function => Some("795E26"),
+        TokenKind::String => Some("A31515"),
+        TokenKind::Number => Some("098658"),
+        TokenKind::Comment => Some("008000"),
+    }
+}
+
+/// Creates the runs of a field for the given instructions, e.g., " INDEX "
+fn field_runs(instructions: String, dirty: bool) -> Vec<Run> {
+    vec![
+        Run::new().add_field_char(FieldCharType::Begin, dirty),
+        Run::new().add_instr_text(InstrText::Unsupported(instructions)),
+        Run::new().add_field_char(FieldCharType::Separate, false),
+        Run::new().add_field_char(FieldCharType::End, false),
+    ]
+}
+
+/// For index term spans, an XE field marking the (colon-separated) terms for Word's index
+fn index_entry_field_runs(span: &InlineSpan) -> Vec<Run> {
+    match span.index_terms() {
+        Some(terms) => {
+            let entry = terms
+                .iter()
+                .map(|term| term.replace('"', "'").replace(':', "\\:"))
+                .collect::<Vec<String>>()
+                .join(":");
+            field_runs(format!(" XE \"{}\" ", entry), false)
+        }
+        None => vec![],
+    }
+}
+
+/// A paragraph with an INDEX field, marked dirty so that Word generates the index (from the XE
+/// fields) when the document is opened
+fn index_field_paragraph() -> Paragraph {
+    field_runs(" INDEX \\h \"A\" \\c \"2\" ".to_string(), true)
+        .into_iter()
+        .fold(Paragraph::new(), |para, run| para.add_run(run))
+}
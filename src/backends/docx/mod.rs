@@ -10,13 +10,21 @@ use docx_rs::Paragraph;
 
 use crate::errors::ConversionError;
 use crate::graph::asg::Asg;
+use crate::highlight::is_builtin_highlighter;
 
 use self::document::DocxRenderError;
 
 /// !Experimental! Renders a Docx file. Some [`Asg`] blocks are still unsupported.
 pub fn render_docx(graph: &Asg, output_path: &Path) -> Result<(), ConversionError> {
     let file = File::create(output_path).unwrap();
-    let mut writer = document::DocxWriter::new();
+    let source_highlighter = graph
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get("source-highlighter"))
+        .is_some_and(|name| is_builtin_highlighter(name));
+    let mut writer = document::DocxWriter::new()
+        .with_labels(graph.labels.clone())
+        .with_source_highlighter(source_highlighter);
     let mut docx = document::asciidocr_default_docx();
 
     // Add document title if present
@@ -32,7 +40,9 @@ pub fn render_docx(graph: &Asg, output_path: &Path) -> Result<(), ConversionErro
     for block in graph.blocks.iter() {
         docx = writer.add_block_to_doc(docx, block)?
     }
-    match docx.build().pack(file) {
+    let mut xml = docx.build();
+    xml.document = writer.insert_equations(xml.document);
+    match xml.pack(file) {
         Ok(_) => Ok(()),
         Err(_) => Err(ConversionError::DocxRender(DocxRenderError::ZipFileError)),
     }
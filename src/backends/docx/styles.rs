@@ -1,6 +1,6 @@
 use docx_rs::{
-    AlignmentType, LineSpacing, Name, ParagraphProperty, RunFonts, RunProperty, SpecialIndentType,
-    Style, StyleType, TableCellProperty, TableProperty,
+    AlignmentType, LineSpacing, Name, ParagraphBorders, ParagraphProperty, RunFonts, RunProperty,
+    Shading, SpecialIndentType, Style, StyleType, TableCellProperty, TableProperty,
 };
 
 #[derive(Debug)]
@@ -10,10 +10,12 @@ pub enum DocumentStyles {
     Monospace,
     Title,
     Heading(usize),
-    SectionTitle(String),
-    SectionText(String),
+    Caption,
+    Example,
+    Sidebar,
     Quote,
     Verse,
+    Admonition,
     ListParagraph,
     ListParagraphContinue,
     OrderedListParagraph(usize),
@@ -31,10 +33,12 @@ impl DocumentStyles {
             DocumentStyles::Monospace => "Monospace".into(),
             DocumentStyles::Title => "Title".into(),
             DocumentStyles::Heading(level) => format!("Heading {}", level),
-            DocumentStyles::SectionTitle(section_name) => format!("{} Title", section_name),
-            DocumentStyles::SectionText(section_name) => format!("{} Text", section_name),
+            DocumentStyles::Caption => "Caption".into(),
+            DocumentStyles::Example => "Example".into(),
+            DocumentStyles::Sidebar => "Sidebar".into(),
             DocumentStyles::Quote => "Quote".into(),
             DocumentStyles::Verse => "Verse".into(),
+            DocumentStyles::Admonition => "Admonition".into(),
             DocumentStyles::ListParagraph => "ListParagraph".into(),
             DocumentStyles::ListParagraphContinue => "ListParagraphContinue".into(),
             DocumentStyles::OrderedListParagraph(id) => format!("NumberedListParagraph_{}", id),
@@ -114,25 +118,44 @@ impl DocumentStyles {
                     .indent(None, Some(SpecialIndentType::FirstLine(0)), None, None)
                     .bold()
             }
-            DocumentStyles::SectionTitle(section_name) => {
-                let style_name = format!("{} Title", section_name);
-                Style::new(&style_name, StyleType::Paragraph)
-                    .name(&style_name)
-                    .based_on("Title")
-                    .indent(None, Some(SpecialIndentType::FirstLine(0)), None, None)
-                    .bold()
+            DocumentStyles::Caption => Style::new("Caption", StyleType::Paragraph)
+                .name("Caption")
+                .based_on("Normal")
+                .next("Normal")
+                .indent(None, Some(SpecialIndentType::FirstLine(0)), None, None)
+                .bold(),
+            DocumentStyles::Example => {
+                // a bordered box; consecutive paragraphs with the same borders share it
+                let mut style = Style::new("Example", StyleType::Paragraph)
+                    .name("Example")
+                    .based_on("Normal")
+                    .indent(
+                        Some(360),
+                        Some(SpecialIndentType::FirstLine(0)),
+                        Some(360),
+                        None,
+                    );
+                style.paragraph_property = style
+                    .paragraph_property
+                    .set_borders(ParagraphBorders::new());
+                style
             }
-            DocumentStyles::SectionText(section_name) => {
-                let style_name = format!("{} Text", section_name);
-                Style::new(&style_name, StyleType::Paragraph)
-                    .name(&style_name)
+            DocumentStyles::Sidebar => {
+                // like examples, but shaded a little darker than admonitions
+                let mut style = Style::new("Sidebar", StyleType::Paragraph)
+                    .name("Sidebar")
                     .based_on("Normal")
                     .indent(
-                        Some(720),
-                        Some(SpecialIndentType::FirstLine(360)),
-                        Some(720),
+                        Some(360),
+                        Some(SpecialIndentType::FirstLine(0)),
+                        Some(360),
                         None,
-                    )
+                    );
+                style.paragraph_property = style
+                    .paragraph_property
+                    .shading(Shading::new().fill("E8E8E8"))
+                    .set_borders(ParagraphBorders::new());
+                style
             }
             DocumentStyles::Quote => Style::new("Quote", StyleType::Paragraph)
                 .name("Quote")
@@ -156,6 +179,23 @@ impl DocumentStyles {
                     Some(0),
                     None,
                 ),
+            DocumentStyles::Admonition => {
+                // a shaded box; consecutive paragraphs with the same borders share it
+                let mut style = Style::new("Admonition", StyleType::Paragraph)
+                    .name("Admonition")
+                    .based_on("Normal")
+                    .indent(
+                        Some(360),
+                        Some(SpecialIndentType::FirstLine(0)),
+                        Some(360),
+                        None,
+                    );
+                style.paragraph_property = style
+                    .paragraph_property
+                    .shading(Shading::new().fill("F2F2F2"))
+                    .set_borders(ParagraphBorders::new());
+                style
+            }
             DocumentStyles::ListParagraph => Style::new("ListParagraph", StyleType::Paragraph)
                 .name("ListParagraph")
                 .based_on("No Spacing")
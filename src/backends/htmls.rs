@@ -1,12 +1,18 @@
-use tera::{Context, Tera};
+use std::collections::HashMap;
+
+use tera::{Context, Tera, Value};
 
 use crate::errors::ConversionError;
 use crate::graph::asg::Asg;
+use crate::graph::substitutions::escape_special_chars;
+use crate::highlight::{is_builtin_highlighter, syntax_for, to_html};
+use crate::stem::{Notation, to_mathml};
 
 static HTMLBOOK_TEMPLATE: &str = include_str!("../../templates/htmlbook/htmlbook.html.tera");
 static BLOCKS_TEMPLATE: &str = include_str!("../../templates/htmlbook/block.html.tera");
 static LEAF_BLOCKS_TEMPLATE: &str = include_str!("../../templates/htmlbook/leafblocks.html.tera");
 static TABLES_TEMPLATE: &str = include_str!("../../templates/htmlbook/tables.html.tera");
+static MEDIA_TEMPLATE: &str = include_str!("../../templates/htmlbook/media.html.tera");
 static INLINES_TEMPLATE: &str = include_str!("../../templates/htmlbook/inline.html.tera");
 
 /// Renders HTMLBook, which is HTML5 compliant, and includes no styles and no extraneous `<div>`
@@ -22,17 +28,170 @@ fn render_from_templates(
     // from their docs
     let mut tera = Tera::default();
     tera.add_raw_templates(templates).expect("failure");
+    tera.register_filter("mathml", mathml);
+    tera.register_filter("source_lines", source_lines);
+    tera.register_filter("highlight", highlight);
+    tera.register_filter("specialchars", specialchars);
+    let mut context = Context::from_serialize(graph)?;
+    context.insert("labels", &graph.labels);
+    context.insert(
+        "source_highlighter",
+        &graph
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get("source-highlighter"))
+            .is_some_and(|name| is_builtin_highlighter(name)),
+    );
+    insert_icon_settings(&mut context, graph);
     Ok(tera
-        .render("htmlbook.html.tera", &Context::from_serialize(graph)?)
+        .render("htmlbook.html.tera", &context)
         .expect("failure"))
 }
 
+/// Adds the admonition icon settings: `icons` ("font" or "image", if icons are on at all),
+/// `iconsdir` (defaulting to "{imagesdir}/icons" or "./images/icons"), and `icontype`
+/// (defaulting to "png")
+fn insert_icon_settings(context: &mut Context, graph: &Asg) {
+    let attributes = graph.attributes.clone().unwrap_or_default();
+    let icons = attributes
+        .get("icons")
+        .map(|icons| if icons == "font" { "font" } else { "image" });
+    let iconsdir =
+        attributes
+            .get("iconsdir")
+            .cloned()
+            .unwrap_or_else(|| match attributes.get("imagesdir") {
+                Some(imagesdir) => format!("{}/icons", imagesdir.trim_end_matches('/')),
+                None => "./images/icons".to_string(),
+            });
+    context.insert("icons", &icons);
+    context.insert("iconsdir", iconsdir.trim_end_matches('/'));
+    context.insert(
+        "icontype",
+        attributes
+            .get("icontype")
+            .map_or("png", |icontype| icontype),
+    );
+}
+
+/// Converts a STEM expression to MathML, e.g., `{{ source | mathml(notation="latexmath") }}`;
+/// `display=true` produces a block equation
+fn mathml(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let source = tera::try_get_value!("mathml", "value", String, value);
+    let notation = args
+        .get("notation")
+        .and_then(|notation| notation.as_str())
+        .and_then(Notation::from_name)
+        .unwrap_or(Notation::AsciiMath);
+    let display = args
+        .get("display")
+        .and_then(|display| display.as_bool())
+        .unwrap_or(false);
+    Ok(Value::String(to_mathml(source.trim(), notation, display)))
+}
+
+/// Applies the "specialchars" substitution to text that was passed through with it, e.g.,
+/// `{{ inline.value | specialchars }}` for the "<b>" in "+<b>+"
+fn specialchars(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = tera::try_get_value!("specialchars", "value", String, value);
+    Ok(Value::String(escape_special_chars(&text)))
+}
+
+/// Highlights the text of a source block's inlines in the given language, leaving any others
+/// (e.g., code callouts) intact, e.g., `{{ block.inlines | highlight(language="rust") }}`
+fn highlight(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let mut inlines = tera::try_get_value!("highlight", "value", Vec<Value>, value);
+    let Some(syntax) = args
+        .get("language")
+        .and_then(|language| language.as_str())
+        .and_then(syntax_for)
+    else {
+        return Ok(Value::Array(inlines));
+    };
+    let is_text = |inline: &Value| inline.get("name") == Some(&Value::from("text"));
+    let segments: Vec<&str> = inlines
+        .iter()
+        .filter(|inline| is_text(inline))
+        .map(|inline| inline["value"].as_str().unwrap_or_default())
+        .collect();
+    let mut highlighted = syntax
+        .tokenize_segments(&segments)
+        .into_iter()
+        .map(|tokens| to_html(&tokens));
+    for inline in inlines.iter_mut().filter(|inline| is_text(inline)) {
+        inline["value"] = Value::from(highlighted.next().unwrap_or_default());
+    }
+    Ok(Value::Array(inlines))
+}
+
+/// Splits the inlines of a source block into numbered lines, noting which are highlighted, e.g.,
+/// `{% for line in block.inlines | source_lines(start=1, highlight=[2, 3]) %}`
+fn source_lines(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let inlines = tera::try_get_value!("source_lines", "value", Vec<Value>, value);
+    let start = args
+        .get("start")
+        .and_then(|start| start.as_u64())
+        .unwrap_or(1);
+    let highlight: Vec<u64> = args
+        .get("highlight")
+        .and_then(|highlight| highlight.as_array())
+        .map(|numbers| {
+            numbers
+                .iter()
+                .filter_map(|number| number.as_u64())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut lines: Vec<Vec<Value>> = vec![vec![]];
+    for inline in inlines {
+        match inline.get("value").and_then(|value| value.as_str()) {
+            Some(text) if inline.get("name") == Some(&Value::from("text")) => {
+                for (idx, part) in text.split('\n').enumerate() {
+                    if idx > 0 {
+                        lines.push(vec![]);
+                    }
+                    if !part.is_empty() {
+                        let mut text_inline = inline.clone();
+                        text_inline["value"] = Value::from(part);
+                        lines.last_mut().unwrap().push(text_inline);
+                    }
+                }
+            }
+            _ => lines.last_mut().unwrap().push(inline),
+        }
+    }
+    // the newlines that open and close the block aren't lines of their own
+    if lines.len() > 1 && lines[0].is_empty() {
+        lines.remove(0);
+    }
+    if lines.len() > 1 && lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    Ok(Value::Array(
+        lines
+            .into_iter()
+            .zip(start..)
+            .map(|(inlines, number)| {
+                tera::to_value(HashMap::from([
+                    ("number", Value::from(number)),
+                    ("highlighted", Value::from(highlight.contains(&number))),
+                    ("inlines", Value::Array(inlines)),
+                ]))
+                .unwrap_or_default()
+            })
+            .collect(),
+    ))
+}
+
 fn gather_htmlbook_templates() -> Vec<(&'static str, &'static str)> {
     vec![
         ("htmlbook.html.tera", HTMLBOOK_TEMPLATE),
         ("block.html.tera", BLOCKS_TEMPLATE),
         ("leafblocks.html.tera", LEAF_BLOCKS_TEMPLATE),
         ("tables.html.tera", TABLES_TEMPLATE),
+        ("media.html.tera", MEDIA_TEMPLATE),
         ("inline.html.tera", INLINES_TEMPLATE),
     ]
 }
@@ -27,3 +27,14 @@ pub enum Backends {
     /// !Experimental! Produces a "manuscript-styled" DOCX document.
     Docx,
 }
+
+impl Backends {
+    /// The file suffix of converted documents, used when rewriting inter-document xrefs
+    pub fn outfilesuffix(&self) -> &str {
+        match self {
+            Backends::Htmlbook | Backends::Json => ".html",
+            #[cfg(feature = "docx")]
+            Backends::Docx => ".docx",
+        }
+    }
+}
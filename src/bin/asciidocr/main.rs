@@ -32,13 +32,13 @@ fn main() {
 }
 
 fn run(args: Cli) -> Result<()> {
-    let graph = match args.do_not_resolve_targets {
-        true => AdocParser::new_no_target_resolution(PathBuf::from(&args.file))
-            .parse(Scanner::new(&read_input(&args)))?,
-        false => {
-            AdocParser::new(PathBuf::from(&args.file)).parse(Scanner::new(&read_input(&args)))?
-        }
+    let parser = match args.do_not_resolve_targets {
+        true => AdocParser::new_no_target_resolution(PathBuf::from(&args.file)),
+        false => AdocParser::new(PathBuf::from(&args.file)),
     };
+    let graph = parser
+        .with_outfilesuffix(args.backend.outfilesuffix())
+        .parse(Scanner::new(&read_input(&args)))?;
     if args.count {
         println!("{} words in {}", graph.word_count(), args.file)
     }
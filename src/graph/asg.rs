@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env::current_dir;
 
 use serde::Serialize;
@@ -8,8 +8,10 @@ use crate::parser::Parser;
 use crate::scanner::Scanner;
 
 use super::blocks::{Block, ParentBlock};
-use super::inlines::Inline;
+use super::index::IndexBuilder;
+use super::inlines::{Inline, NamedFootnote};
 use super::nodes::{Header, Location, NodeTypes};
+use super::numbering::SectionNumbering;
 
 /// Abstract Syntax Graph used to represent an asciidoc document
 /// roughly meaning to follow the "official" schema:
@@ -32,6 +34,9 @@ pub struct Asg {
     #[serde(skip)]
     /// Has of all IDs in the document, and the references they point to
     document_id_hash: HashMap<String, Vec<Inline>>,
+    #[serde(skip)]
+    /// Built-in labels (e.g., admonition captions), localized or set via document attributes
+    pub labels: HashMap<String, String>,
     /// Document contents
     pub blocks: Vec<Block>,
     /// Marks the start and end of document
@@ -60,6 +65,7 @@ impl Asg {
             header: None,
             document_id: "".to_string(),
             document_id_hash: HashMap::new(),
+            labels: HashMap::new(),
             blocks: vec![],
             location: vec![Location::default()],
         }
@@ -85,6 +91,9 @@ impl Asg {
     pub fn push_block(&mut self, mut block: Block) -> Result<(), AsgError> {
         block.consolidate_locations();
         self.document_id_hash.extend(block.id_hashes());
+        for inline in block.inlines() {
+            self.document_id_hash.extend(inline.id_hashes());
+        }
         if block.is_section() {
             if let Some(possible_section) = self.blocks.last_mut() {
                 if possible_section.takes_block_of_type(&block) {
@@ -101,6 +110,57 @@ impl Asg {
         Ok(())
     }
 
+    /// Numbers sections according to the document attributes (e.g., `sectnums`), updating the
+    /// xref text of numbered sections if an `xrefstyle` is given
+    pub fn number_sections(&mut self, attributes: &HashMap<String, String>) {
+        let mut numbering = SectionNumbering::new(attributes);
+        numbering.number_blocks(&mut self.blocks);
+        self.document_id_hash.extend(numbering.xref_texts);
+    }
+
+    /// Generates the index for any `[index]` sections from the index terms in the document
+    pub fn generate_index(&mut self) {
+        IndexBuilder::new(self.document_id_hash.keys().cloned().collect()).build(&mut self.blocks);
+    }
+
+    /// Resolves inter-document xrefs (e.g., `<<other.adoc#some_id>>`), given a check for whether
+    /// the referenced document was included in this parse and the suffix of converted documents
+    pub fn resolve_document_xrefs(&mut self, is_included: &dyn Fn(&str) -> bool, suffix: &str) {
+        for block in self.blocks.iter_mut() {
+            for inline in block.inlines_mut() {
+                inline.resolve_document_xref(is_included, suffix);
+            }
+        }
+    }
+
+    /// Unlinks any code callout markers whose callout list items don't exist (i.e., aren't among
+    /// the given IDs), warning about each
+    pub fn unlink_orphan_callouts(&mut self, item_ids: &HashSet<String>) {
+        for block in self.blocks.iter_mut() {
+            for inline in block.inlines_mut() {
+                if let Inline::InlineSpan(span) = inline {
+                    span.unlink_callout(item_ids);
+                }
+            }
+        }
+    }
+
+    /// Replaces any references to named footnotes that are never defined with plain text, warning
+    /// about each
+    pub fn unlink_undefined_footnotes(&mut self) {
+        let mut defined_ids: HashSet<String> = HashSet::new();
+        for block in self.blocks.iter_mut() {
+            for inline in block.inlines_mut() {
+                defined_ids.extend(inline.footnote_definition_ids());
+            }
+        }
+        for block in self.blocks.iter_mut() {
+            for inline in block.inlines_mut() {
+                inline.unlink_undefined_footnote(&defined_ids);
+            }
+        }
+    }
+
     /// Consolidates location information about the tree
     pub fn consolidate_locations(&mut self) {
         if let Some(last_block) = self.blocks.last_mut() {
@@ -130,10 +190,21 @@ impl Asg {
     pub fn standardize_footnotes(&mut self) -> Result<(), AsgError> {
         // Until the spec says otherwise, put footnote definitions in leaf blocks
         let mut footnote_defs: Vec<Block> = vec![];
+        let mut named_footnotes: HashMap<String, NamedFootnote> = HashMap::new();
         for block in self.blocks.iter_mut() {
-            footnote_defs.extend(
-                block.extract_footnote_definitions(footnote_defs.len(), &self.document_id)?,
-            );
+            footnote_defs.extend(block.extract_footnote_definitions(
+                footnote_defs.len(),
+                &self.document_id,
+                &mut named_footnotes,
+            )?);
+        }
+        // reused named footnotes link back to each of their references
+        for named_footnote in named_footnotes.values() {
+            if let Some(Block::LeafBlock(definition)) =
+                footnote_defs.get_mut(named_footnote.number - 1)
+            {
+                definition.add_footnote_back_references(named_footnote, &self.document_id);
+            }
         }
         // create a parent block to hold the footnote definitions
         self.push_block(Block::ParentBlock(ParentBlock::new_footnotes_container(
@@ -161,6 +232,7 @@ mod tests {
     use super::*;
     use crate::graph::blocks::*;
     use crate::graph::inlines::*;
+    use crate::graph::metadata::ElementMetadata;
 
     #[test]
     fn consolidate_footnotes() {
@@ -201,4 +273,115 @@ mod tests {
         };
         assert_eq!(iref.target, "test_footnotedef_1");
     }
+
+    #[test]
+    fn reused_named_footnotes() {
+        let named_footnote = |text: &str| {
+            let mut footnote = InlineSpan::new(
+                InlineSpanVariant::Footnote,
+                InlineSpanForm::Constrained,
+                vec![],
+            );
+            footnote.metadata = Some(ElementMetadata::new_with_attribute(
+                "footnote_id",
+                "disclaimer".to_string(),
+            ));
+            if !text.is_empty() {
+                footnote
+                    .inlines
+                    .push(Inline::InlineLiteral(InlineLiteral::new(
+                        InlineLiteralName::Text,
+                        text.to_string(),
+                        vec![],
+                    )));
+            }
+            Inline::InlineSpan(footnote)
+        };
+        let some_leaf = Block::LeafBlock(LeafBlock::new(
+            LeafBlockName::Paragraph,
+            LeafBlockForm::Paragraph,
+            None,
+            vec![],
+            vec![
+                named_footnote("Opinions are my own."),
+                named_footnote(""),
+                named_footnote(""),
+            ],
+        ));
+        let mut graph = Asg::new();
+        let _ = graph.push_block(some_leaf);
+        let _ = graph.standardize_footnotes();
+        // one definition, referenced three times
+        let Some(Block::LeafBlock(leaf)) = graph.blocks.first() else {
+            panic!("Destroyed the block we were only supposed to modify")
+        };
+        let ref_targets: Vec<String> = leaf
+            .inlines()
+            .iter()
+            .map(|inline| {
+                let Inline::InlineSpan(footnoteref) = inline else {
+                    panic!("Missing footnote ref in leaf block")
+                };
+                let Some(Inline::InlineRef(iref)) = footnoteref.inlines.first() else {
+                    panic!("Missing footnote ref link")
+                };
+                assert_eq!(iref.inlines[0].extract_values_to_string(), "1");
+                iref.target.clone()
+            })
+            .collect();
+        assert_eq!(ref_targets, vec!["_footnotedef_1"; 3]);
+        // with links back to each reference
+        let Some(Block::ParentBlock(footnotes)) = graph.blocks.last() else {
+            panic!("Missing footnotes container")
+        };
+        let Some(Block::LeafBlock(definition)) = footnotes.blocks.first() else {
+            panic!("Missing footnote definition")
+        };
+        assert_eq!(footnotes.blocks.len(), 1);
+        let back_refs: Vec<String> = definition
+            .inlines()
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::InlineRef(iref) => Some(iref.target.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            back_refs,
+            vec!["_footnoteref_1", "_footnoteref_1_2", "_footnoteref_1_3"]
+        );
+    }
+
+    #[test]
+    fn footnotes_in_nested_blocks_are_numbered_in_order() {
+        let mut graph: Asg = "Intro.footnote:[First.]\n\n\
+            == Section\n\n\
+            Section text.footnote:[Second.]\n\n\
+            ====\nExample.footnote:[Third.]\n====\n\n\
+            Term one:: Definition.footnote:[Fourth.]\n\
+            Term two:: Definition.footnote:[Fifth.]\n\n\
+            * Item.footnote:[Sixth.]\n\n\
+            After.footnote:[Seventh.]\n"
+            .parse()
+            .expect("Unable to parse document");
+        let _ = graph.standardize_footnotes();
+        let Some(Block::ParentBlock(footnotes)) = graph.blocks.last() else {
+            panic!("Missing footnotes container")
+        };
+        let def_ids: Vec<String> = footnotes
+            .blocks
+            .iter()
+            .map(|block| {
+                let Block::LeafBlock(definition) = block else {
+                    panic!("Footnote definition was not a leaf block")
+                };
+                let Some(ref metadata) = definition.metadata else {
+                    panic!("Footnote definition is missing metadata")
+                };
+                metadata.element_id().unwrap()
+            })
+            .collect();
+        let expected: Vec<String> = (1..=7).map(|n| format!("_footnotedef_{n}")).collect();
+        assert_eq!(def_ids, expected);
+    }
 }
@@ -5,12 +5,14 @@ use serde::Serialize;
 
 use crate::errors::{BlockError, ParserError};
 use crate::graph::{
-    inlines::Inline,
+    index::IndexCategory,
+    inlines::{Inline, InlineLiteral, InlineLiteralName, NamedFootnote},
     lists::{DList, DListItem, List, ListItem, ListVariant},
     metadata::ElementMetadata,
     nodes::{Location, NodeTypes},
 };
 use crate::scanner::tokens::{Token, TokenType};
+use crate::utils::extract_line_numbers;
 
 /// Blocks Enum, containing all possible document blocks
 #[derive(Serialize, PartialEq, Clone, Debug)]
@@ -222,7 +224,9 @@ impl Block {
                 // check for an implicit header
                 if first_cell_line == table.location[0].line + 1 {
                     if let Some(ref mut metadata) = table.metadata {
-                        metadata.options.push("header".to_string());
+                        if !metadata.has_option("header") {
+                            metadata.options.push("header".to_string());
+                        }
                     } else {
                         let mut metadata = ElementMetadata::default();
                         metadata.options.push("header".to_string());
@@ -285,6 +289,83 @@ impl Block {
             Block::Section(block) => Some(block.inlines.clone()),
             Block::ParentBlock(block) => Some(block.title.clone()),
             Block::BlockMacro(block) => Some(block.caption.clone()),
+            Block::LeafBlock(block) => Some(block.title.clone()),
+            _ => None,
+        }
+    }
+
+    /// For titled figures, tables, examples, and listings that have not yet been captioned,
+    /// returns the kind of caption to apply (e.g., "figure", as in `:figure-caption:`)
+    pub fn caption_kind(&self) -> Option<&'static str> {
+        match self {
+            Block::BlockMacro(block)
+                if block.name == BlockMacroName::Image
+                    && !block.caption.is_empty()
+                    && block.caption_prefix.is_none() =>
+            {
+                Some("figure")
+            }
+            Block::ParentBlock(block)
+                if !block.title.is_empty() && block.caption_prefix.is_none() =>
+            {
+                match block.name {
+                    ParentBlockName::Table => Some("table"),
+                    // collapsible examples are titled, but not captioned
+                    ParentBlockName::Example if !block.is_collapsible() => Some("example"),
+                    _ => None,
+                }
+            }
+            Block::LeafBlock(block)
+                if block.name == LeafBlockName::Listing
+                    && !block.title.is_empty()
+                    && block.caption_prefix.is_none() =>
+            {
+                Some("listing")
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the caption prefix and, if the block is numbered, the xref text
+    pub fn set_caption(&mut self, prefix: String, reftext: Option<String>) {
+        match self {
+            Block::BlockMacro(block) => {
+                block.caption_prefix = Some(prefix);
+                block.caption_reftext = reftext;
+            }
+            Block::ParentBlock(block) => {
+                block.caption_prefix = Some(prefix);
+                block.caption_reftext = reftext;
+            }
+            Block::LeafBlock(block) => {
+                block.caption_prefix = Some(prefix);
+                block.caption_reftext = reftext;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the block's metadata, if any
+    pub fn metadata(&self) -> Option<&ElementMetadata> {
+        match self {
+            Block::Section(block) => block.metadata.as_ref(),
+            Block::List(block) => block.metadata.as_ref(),
+            Block::ListItem(block) => block.metadata.as_ref(),
+            Block::DList(block) => block.metadata.as_ref(),
+            Block::DListItem(block) => block.metadata.as_ref(),
+            Block::BlockMacro(block) => block.metadata.as_ref(),
+            Block::LeafBlock(block) => block.metadata.as_ref(),
+            Block::ParentBlock(block) => block.metadata.as_ref(),
+            Block::TableCell(block) => block.metadata.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn caption_reftext(&self) -> Option<String> {
+        match self {
+            Block::BlockMacro(block) => block.caption_reftext.clone(),
+            Block::ParentBlock(block) => block.caption_reftext.clone(),
+            Block::LeafBlock(block) => block.caption_reftext.clone(),
             _ => None,
         }
     }
@@ -372,7 +453,10 @@ impl Block {
                     inlines.extend(child.inlines_mut())
                 }
             }
-            Block::LeafBlock(block) => inlines.extend(block.inlines.iter_mut()),
+            Block::LeafBlock(block) => {
+                inlines.extend(block.title.iter_mut());
+                inlines.extend(block.inlines.iter_mut())
+            }
             Block::TableCell(block) => inlines.extend(block.inlines.iter_mut()),
             _ => {} // remaining blocks don't have inlines
         }
@@ -386,6 +470,7 @@ impl Block {
         &mut self,
         footnote_count: usize,
         document_id: &str,
+        named_footnotes: &mut HashMap<String, NamedFootnote>,
     ) -> Result<Vec<Block>, BlockError> {
         // setup references
         let mut local_count = footnote_count;
@@ -396,64 +481,69 @@ impl Block {
             // parents
             Block::Section(block) => {
                 for child in block.blocks.iter_mut() {
-                    let child_footnoes =
-                        child.extract_footnote_definitions(extracted.len(), document_id)?;
+                    let child_footnoes = child.extract_footnote_definitions(
+                        local_count,
+                        document_id,
+                        named_footnotes,
+                    )?;
                     local_count += child_footnoes.len();
                     extracted.extend(child_footnoes);
                 }
             }
             Block::ParentBlock(block) => {
                 for child in block.blocks.iter_mut() {
-                    let child_footnoes =
-                        child.extract_footnote_definitions(extracted.len(), document_id)?;
+                    let child_footnoes = child.extract_footnote_definitions(
+                        local_count,
+                        document_id,
+                        named_footnotes,
+                    )?;
                     local_count += child_footnoes.len();
                     extracted.extend(child_footnoes);
                 }
             }
             Block::List(block) => {
                 for child in block.items.iter_mut() {
-                    let child_footnoes =
-                        child.extract_footnote_definitions(extracted.len(), document_id)?;
+                    let child_footnoes = child.extract_footnote_definitions(
+                        local_count,
+                        document_id,
+                        named_footnotes,
+                    )?;
                     local_count += child_footnoes.len();
                     extracted.extend(child_footnoes);
                 }
             }
             Block::DList(block) => {
                 for child in block.items.iter_mut() {
-                    let child_footnoes =
-                        child.extract_footnote_definitions(extracted.len(), document_id)?;
+                    let child_footnoes = child.extract_footnote_definitions(
+                        local_count,
+                        document_id,
+                        named_footnotes,
+                    )?;
+                    local_count += child_footnoes.len();
                     extracted.extend(child_footnoes);
                 }
             }
             Block::ListItem(block) => {
-                let child_footnotes = block.extract_footnotes(extracted.len(), document_id)?;
+                let child_footnotes =
+                    block.extract_footnotes(local_count, document_id, named_footnotes)?;
                 extracted.extend(child_footnotes);
             }
             Block::DListItem(block) => {
-                let child_footnotes = block.extract_footnotes(extracted.len(), document_id)?;
+                let child_footnotes =
+                    block.extract_footnotes(local_count, document_id, named_footnotes)?;
                 extracted.extend(child_footnotes);
             }
             // nonparents
             Block::LeafBlock(block) => {
                 for idx in 0..block.inlines.len() {
                     if block.inlines[idx].is_footnote() {
-                        local_count += 1;
-                        let inline_span = block.inlines.remove(idx);
-                        let Inline::InlineSpan(mut footnote) = inline_span else {
-                            return Err(BlockError::Footnote("Bad is_footnote match".to_string()));
-                        };
-                        // deconstruct it
-                        let (definition_id, replacement_span, footnote_contents) =
-                            footnote.deconstruct_footnote(local_count, document_id);
-                        // add the relevant stuff to the return
-                        extracted.push(Block::LeafBlock(
-                            LeafBlock::new_footnote_def_from_id_and_inlines(
-                                definition_id,
-                                footnote_contents,
-                            ),
-                        ));
-                        // put the reference back where the span was
-                        block.inlines.insert(idx, replacement_span);
+                        extracted.extend(extract_footnote(
+                            &mut block.inlines,
+                            idx,
+                            &mut local_count,
+                            document_id,
+                            named_footnotes,
+                        )?);
                     }
                 }
             }
@@ -465,22 +555,39 @@ impl Block {
     }
 
     pub fn create_id(&mut self) {
-        if let Block::Section(section) = self {
-            if section.id == *"" {
-                let mut id = String::new();
-                for inline in &section.inlines {
-                    id.push_str(&inline.extract_values_to_string())
-                }
-                id = id.replace(' ', "-");
-                section.id = id
+        if let Block::Section(section) = self
+            && section.id == *""
+        {
+            let mut id = String::new();
+            for inline in &section.inlines {
+                id.push_str(&inline.extract_values_to_string())
             }
+            id = id.replace(' ', "-");
+            section.id = id
         }
     }
 
     pub fn id_hashes(&self) -> HashMap<String, Vec<Inline>> {
         let mut block_id_hash = HashMap::new();
         if let Some(id) = self.id() {
-            if let Some(title) = self.title() {
+            if let Some(reftext) = self.caption_reftext() {
+                // the reference text stands in for the title, so it gets the title's location
+                let location = self
+                    .title()
+                    .unwrap_or_default()
+                    .iter()
+                    .fold(vec![], |locations, inline| {
+                        Location::reconcile(locations, inline.locations())
+                    });
+                block_id_hash.insert(
+                    id,
+                    vec![Inline::InlineLiteral(InlineLiteral::new(
+                        InlineLiteralName::Text,
+                        reftext,
+                        location,
+                    ))],
+                );
+            } else if let Some(title) = self.title() {
                 block_id_hash.insert(id, title);
             } else {
                 block_id_hash.insert(id, vec![]);
@@ -524,13 +631,7 @@ impl Block {
 
     pub fn id(&self) -> Option<String> {
         match self {
-            Block::Section(block) => {
-                if let Some(metadata) = &block.metadata {
-                    metadata.attributes.get("id").cloned()
-                } else {
-                    None
-                }
-            }
+            Block::Section(block) => block.element_id(),
             Block::List(block) => {
                 if let Some(metadata) = &block.metadata {
                     metadata.attributes.get("id").cloned()
@@ -814,6 +915,15 @@ pub struct Section {
     #[serde(skip_serializing_if = "Option::is_none")]
     metadata: Option<ElementMetadata>,
     pub level: usize,
+    /// The section number as displayed, e.g., "2.3", "Appendix A:", or "Part I:"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    /// Whether `sectnums` was set when the section was parsed
+    #[serde(skip)]
+    pub numbered: bool,
+    /// The generated index, for `[index]` sections
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub index: Vec<IndexCategory>,
     pub blocks: Vec<Block>,
     location: Vec<Location>,
 }
@@ -835,14 +945,48 @@ impl Section {
             reftext: vec![], // added later
             metadata: None,
             level,
+            number: None,
+            numbered: false,
+            index: vec![],
             blocks: vec![],
             location: vec![first_location],
         }
     }
 
+    /// Returns the section style (e.g., "appendix", "preface"), if any
+    pub fn style(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.attributes.get("positional_1"))
+            .map(|s| s.as_str())
+    }
+
     pub fn title(&self) -> Vec<Inline> {
         self.inlines.clone()
     }
+
+    /// Returns the title as plain text, e.g., for index references
+    pub fn title_text(&self) -> String {
+        self.inlines
+            .iter()
+            .map(|inline| inline.extract_values_to_string())
+            .collect()
+    }
+
+    /// Returns the section ID, if it has one
+    pub fn element_id(&self) -> Option<String> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.element_id())
+    }
+
+    /// Sets the section ID, e.g., one generated from the title
+    pub fn set_id(&mut self, id: String) {
+        self.metadata
+            .get_or_insert_with(ElementMetadata::default)
+            .attributes
+            .insert("id".to_string(), id);
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -905,6 +1049,12 @@ pub struct BlockMacro {
     pub target: String,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub caption: Vec<Inline>,
+    /// The numbered caption prefix, e.g., "Figure 3."
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption_prefix: Option<String>,
+    /// The xref text for a numbered block, e.g., "Figure 3"
+    #[serde(skip)]
+    pub caption_reftext: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     metadata: Option<ElementMetadata>,
     location: Vec<Location>,
@@ -938,6 +1088,8 @@ impl BlockMacro {
             form: "macro".to_string(),
             target,
             caption: vec![],
+            caption_prefix: None,
+            caption_reftext: None,
             metadata,
             location,
         }
@@ -951,6 +1103,44 @@ impl BlockMacro {
         BlockMacro::new(BlockMacroName::Image, target, metadata, locations)
     }
 
+    /// Creates an audio or video block; a video's positional attributes are its poster (or
+    /// "youtube" or "vimeo", if the target is a video ID), width, and height
+    pub fn new_media_block(
+        name: BlockMacroName,
+        target: String,
+        metadata: Option<ElementMetadata>,
+        locations: Vec<Location>,
+    ) -> Self {
+        let mut block = BlockMacro::new(name, target, metadata, locations);
+        if block.name == BlockMacroName::Video
+            && let Some(metadata) = block.metadata.as_mut()
+        {
+            for (idx, key) in ["poster", "width", "height"].iter().enumerate() {
+                if let Some(value) = metadata
+                    .attributes
+                    .remove(&format!("positional_{}", idx + 1))
+                {
+                    metadata.attributes.entry(key.to_string()).or_insert(value);
+                }
+            }
+        }
+        block
+    }
+
+    /// The URL of an audio or video block's target, including YouTube and Vimeo video IDs
+    pub fn media_url(&self) -> String {
+        let poster = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("poster"))
+            .map(|poster| poster.as_str());
+        match poster {
+            Some("youtube") => format!("https://www.youtube.com/watch?v={}", self.target),
+            Some("vimeo") => format!("https://vimeo.com/{}", self.target),
+            _ => self.target.clone(),
+        }
+    }
+
     pub fn add_metadata(mut self, incoming_metadata: &ElementMetadata) -> Self {
         match self.metadata {
             Some(ref mut metadata) => metadata.add_metadata_from_other(incoming_metadata),
@@ -969,6 +1159,17 @@ pub struct LeafBlock {
     form: LeafBlockForm,
     #[serde(skip_serializing_if = "Option::is_none")]
     delimiter: Option<String>, // if it's a delimited block, then we provide the delimiter
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub title: Vec<Inline>,
+    /// The numbered caption prefix, e.g., "Figure 3."
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption_prefix: Option<String>,
+    /// The xref text for a numbered block, e.g., "Figure 3"
+    #[serde(skip)]
+    pub caption_reftext: Option<String>,
+    /// Line numbering and highlighting, for source blocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_lines: Option<SourceLines>,
     inlines: Vec<Inline>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<ElementMetadata>,
@@ -1000,6 +1201,47 @@ impl LeafBlockForm {
     }
 }
 
+/// The line numbering and highlighted lines of a source block, e.g.,
+/// `[source,rust,linenums,start=3,highlight=4..5]`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SourceLines {
+    pub linenums: bool,
+    pub start: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlight: Vec<usize>,
+}
+
+impl SourceLines {
+    /// Reads the options from the metadata of a source block with the given number of lines
+    pub fn from_metadata(metadata: &ElementMetadata, line_count: usize) -> Option<Self> {
+        let linenums = metadata.has_option("linenums");
+        let start = metadata
+            .attributes
+            .get("start")
+            .and_then(|start| start.parse::<usize>().ok())
+            .unwrap_or(1);
+        // e.g., "highlight=1..99999999" only highlights the lines the block has
+        let last = start.saturating_add(line_count).saturating_sub(1);
+        let highlight = metadata
+            .attributes
+            .get("highlight")
+            .map(|numbers| extract_line_numbers(numbers, last))
+            .unwrap_or_default();
+        if !linenums && highlight.is_empty() {
+            return None;
+        }
+        Some(SourceLines {
+            linenums,
+            start,
+            highlight,
+        })
+    }
+
+    pub fn is_highlighted(&self, number: usize) -> bool {
+        self.highlight.contains(&number)
+    }
+}
+
 impl PartialEq for LeafBlock {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
@@ -1020,6 +1262,10 @@ impl LeafBlock {
             node_type: NodeTypes::Block,
             form,
             delimiter,
+            title: vec![],
+            caption_prefix: None,
+            caption_reftext: None,
+            source_lines: None,
             inlines,
             metadata: None,
             location,
@@ -1032,6 +1278,10 @@ impl LeafBlock {
             node_type: NodeTypes::Block,
             form: LeafBlockForm::Delimited,
             delimiter: Some(token.text()),
+            title: vec![],
+            caption_prefix: None,
+            caption_reftext: None,
+            source_lines: None,
             inlines: vec![],
             metadata: None,
             location: token.locations(),
@@ -1064,6 +1314,89 @@ impl LeafBlock {
     pub fn inlines(&self) -> Vec<Inline> {
         self.inlines.clone()
     }
+    /// The `indent=N` attribute of a verbatim block, if any
+    pub fn indent(&self) -> Option<usize> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("indent"))
+            .and_then(|indent| indent.parse::<usize>().ok())
+    }
+
+    /// Strips the common indentation from the lines of a verbatim block, then indents each line
+    /// by the given number of spaces
+    pub fn reindent(&mut self, indent: usize) {
+        let mut at_line_start = true;
+        let mut common_indentation: Option<usize> = None;
+        for inline in self.inlines.iter() {
+            match inline {
+                Inline::InlineLiteral(literal) => {
+                    if let Some(width) = literal.min_indentation(at_line_start) {
+                        common_indentation =
+                            Some(common_indentation.map_or(width, |common| common.min(width)));
+                    }
+                    at_line_start = literal.ends_line();
+                }
+                _ => at_line_start = false,
+            }
+        }
+        let strip = common_indentation.unwrap_or(0);
+        at_line_start = true;
+        for inline in self.inlines.iter_mut() {
+            match inline {
+                Inline::InlineLiteral(literal) => {
+                    let ends_line = literal.ends_line();
+                    literal.reindent(at_line_start, strip, indent);
+                    at_line_start = ends_line;
+                }
+                _ => at_line_start = false,
+            }
+        }
+    }
+
+    /// Records the line numbering and highlighting options of a source block, if any
+    pub fn set_source_lines(&mut self) {
+        if self.name == LeafBlockName::Listing {
+            let line_count = self.text_lines().len();
+            self.source_lines = self
+                .metadata
+                .as_ref()
+                .and_then(|metadata| SourceLines::from_metadata(metadata, line_count));
+        }
+    }
+
+    /// The lines of a verbatim block's text, without the newlines that open and close the block
+    pub fn text_lines(&self) -> Vec<String> {
+        let text: String = self
+            .inlines
+            .iter()
+            .map(|inline| inline.extract_values_to_string())
+            .collect();
+        let text = text.strip_prefix('\n').unwrap_or(&text);
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        text.split('\n').map(|line| line.to_string()).collect()
+    }
+
+    /// Literal paragraphs (i.e., indented paragraphs) have their common indentation stripped
+    /// (unless told otherwise with `indent=N`) and lose the newline(s) that closed them
+    pub fn finish_literal_paragraph(&mut self) {
+        if let Some(Inline::InlineLiteral(literal)) = self.inlines.last_mut() {
+            literal.trim_trailing_newlines();
+        }
+        self.reindent(self.indent().unwrap_or(0));
+    }
+
+    /// For footnote definitions of reused named footnotes, adds links back to the references
+    /// after the first (which follow the link back to the first reference)
+    pub fn add_footnote_back_references(
+        &mut self,
+        named_footnote: &NamedFootnote,
+        document_id: &str,
+    ) {
+        let back_references = named_footnote.back_references(document_id, self.location.clone());
+        let idx = self.inlines.len().min(1);
+        self.inlines.splice(idx..idx, back_references);
+    }
+
     pub fn new_footnote_def_from_id_and_inlines(
         definition_id: String,
         inlines: Vec<Inline>,
@@ -1073,6 +1406,10 @@ impl LeafBlock {
             node_type: NodeTypes::Block,
             form: LeafBlockForm::Paragraph,
             delimiter: None,
+            title: vec![],
+            caption_prefix: None,
+            caption_reftext: None,
+            source_lines: None,
             inlines,
             metadata: Some(ElementMetadata::new_with_id_and_roles(
                 definition_id,
@@ -1096,6 +1433,12 @@ pub struct ParentBlock {
     pub blocks: Vec<Block>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub title: Vec<Inline>,
+    /// The numbered caption prefix, e.g., "Figure 3."
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption_prefix: Option<String>,
+    /// The xref text for a numbered block, e.g., "Figure 3"
+    #[serde(skip)]
+    pub caption_reftext: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<ElementMetadata>,
     pub location: Vec<Location>,
@@ -1138,6 +1481,7 @@ pub enum ParentBlockVarient {
     Warning,
 }
 
+/// English labels; see [`crate::graph::labels`] for localized ones
 impl Display for ParentBlockVarient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1150,6 +1494,13 @@ impl Display for ParentBlockVarient {
     }
 }
 
+impl ParentBlockVarient {
+    /// The document attribute holding the (localizable) label, e.g., "note-caption"
+    pub fn caption_attribute(&self) -> String {
+        format!("{}-caption", self.to_string().to_lowercase())
+    }
+}
+
 impl ParentBlock {
     pub fn new(
         name: ParentBlockName,
@@ -1166,6 +1517,8 @@ impl ParentBlock {
             delimiter,
             blocks,
             title: vec![],
+            caption_prefix: None,
+            caption_reftext: None,
             metadata: None,
             location,
         }
@@ -1264,6 +1617,61 @@ impl ParentBlock {
             None => Err(BlockError::Location),
         }
     }
+
+    /// An admonition's label, e.g., "Note": its `caption` attribute, if given, or else its
+    /// (localized) built-in label
+    pub fn admonition_label(&self, labels: &HashMap<String, String>) -> Option<String> {
+        let variant = self.variant.as_ref()?;
+        if let Some(caption) = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("caption"))
+        {
+            return Some(caption.clone());
+        }
+        Some(
+            labels
+                .get(&variant.caption_attribute())
+                .cloned()
+                .unwrap_or_else(|| variant.to_string()),
+        )
+    }
+
+    /// Admonition paragraphs styled with "[NOTE]" and the like can instead style the example
+    /// block that follows them
+    pub fn accepts_delimiter(&self, example: &ParentBlock) -> bool {
+        self.name == ParentBlockName::Admonition
+            && self.blocks.is_empty()
+            && self.delimiter.starts_with('[')
+            && example.name == ParentBlockName::Example
+    }
+
+    /// Turns an admonition paragraph into a delimited admonition, taking on the delimiter (and
+    /// title, if any) of the example block it styles
+    pub fn delimit(&mut self, example: ParentBlock) {
+        self.delimiter = example.delimiter;
+        if !example.title.is_empty() {
+            self.title = example.title;
+        }
+        self.location = Location::reconcile(self.location.clone(), example.location);
+    }
+
+    /// Delimited blocks styled as another kind of block, e.g., admonitions opened by an example
+    /// block delimiter or sidebars opened by an open block delimiter, are closed by the delimiter
+    /// that opened them
+    pub fn is_closed_by(&self, closing: &ParentBlock) -> bool {
+        self.delimiter == closing.delimiter
+    }
+
+    /// Example blocks marked `%collapsible` are hidden (unless also marked `%open`) behind their
+    /// title in HTML output
+    pub fn is_collapsible(&self) -> bool {
+        self.name == ParentBlockName::Example
+            && self
+                .metadata
+                .as_ref()
+                .is_some_and(|metadata| metadata.has_option("collapsible"))
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -1294,6 +1702,45 @@ impl TableCell {
     }
 }
 
+/// Extracts the footnote at the given index, putting a reference to its definition in its place,
+/// and returns the definition (incrementing the count); footnotes that reuse a named footnote
+/// (e.g., a second "footnote:disclaimer[]") refer back to the existing definition instead
+pub fn extract_footnote(
+    inlines: &mut Vec<Inline>,
+    idx: usize,
+    count: &mut usize,
+    document_id: &str,
+    named_footnotes: &mut HashMap<String, NamedFootnote>,
+) -> Result<Option<Block>, BlockError> {
+    let Inline::InlineSpan(mut footnote) = inlines.remove(idx) else {
+        return Err(BlockError::Footnote("Bad is_footnote match".to_string()));
+    };
+    let footnote_id = footnote.footnote_id();
+    if footnote.inlines.is_empty()
+        && let Some(named_footnote) = footnote_id
+            .as_ref()
+            .and_then(|id| named_footnotes.get_mut(id))
+    {
+        inlines.insert(
+            idx,
+            footnote.deconstruct_footnote_reuse(named_footnote, document_id),
+        );
+        return Ok(None);
+    }
+    *count += 1;
+    if let Some(id) = footnote_id {
+        named_footnotes.insert(id, NamedFootnote::new(*count));
+    }
+    // deconstruct it
+    let (definition_id, replacement_span, footnote_contents) =
+        footnote.deconstruct_footnote(*count, document_id);
+    // put the reference back where the span was
+    inlines.insert(idx, replacement_span);
+    Ok(Some(Block::LeafBlock(
+        LeafBlock::new_footnote_def_from_id_and_inlines(definition_id, footnote_contents),
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::graph::inlines::{
@@ -1324,7 +1771,7 @@ mod tests {
             vec![footnote],
         ));
         let extracted = some_leaf
-            .extract_footnote_definitions(0, "")
+            .extract_footnote_definitions(0, "", &mut HashMap::new())
             .expect("Error extracting footnote definitions");
         let Block::LeafBlock(result) = some_leaf else {
             panic!("Destroyed the leaf block somehow")
@@ -1356,4 +1803,14 @@ mod tests {
         assert_eq!(inline.variant, InlineRefVariant::Xref);
         assert_eq!(inline.target, "_footnoteref_1");
     }
+
+    #[test]
+    fn source_lines_highlight_is_limited_to_block_lines() {
+        let mut metadata =
+            ElementMetadata::new_with_attribute("highlight", "2..99999999999".into());
+        metadata.attributes.insert("start".into(), "2".into());
+        let lines = SourceLines::from_metadata(&metadata, 3).unwrap();
+        assert_eq!(lines.highlight, vec![2, 3, 4]);
+        assert!(!lines.is_highlighted(5));
+    }
 }
@@ -0,0 +1,325 @@
+//! Index generation for documents with an `[index]` section: gathers the index terms (e.g.,
+//! `((Big cats))` or `(((Cats, Big)))`) from the graph, sorted and grouped by their first letter,
+//! with references back to the sections containing them.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::blocks::{Block, Section};
+use super::inlines::Inline;
+
+/// Index entries sharing a first letter, e.g., "C" for "Cats" and "cheetahs"
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IndexCategory {
+    pub letter: String,
+    pub entries: Vec<IndexEntry>,
+}
+
+/// A term in the index, with references to where it appears and any subordinate (secondary or
+/// tertiary) entries
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IndexEntry {
+    pub term: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<IndexReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<IndexEntry>,
+}
+
+/// Points to the section containing an index term
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IndexReference {
+    /// The ID of the section
+    pub target: String,
+    /// The section title
+    pub text: String,
+}
+
+impl IndexEntry {
+    fn new(term: &str) -> Self {
+        IndexEntry {
+            term: term.to_string(),
+            references: vec![],
+            entries: vec![],
+        }
+    }
+
+    /// Adds a reference to the entry at the given term path, e.g., ["Cats", "Big"], creating
+    /// entries as needed
+    fn add(entries: &mut Vec<IndexEntry>, terms: &[String], reference: Option<&IndexReference>) {
+        let Some((term, subterms)) = terms.split_first() else {
+            return;
+        };
+        let idx = match entries.iter().position(|entry| &entry.term == term) {
+            Some(idx) => idx,
+            None => {
+                entries.push(IndexEntry::new(term));
+                entries.len() - 1
+            }
+        };
+        let entry = &mut entries[idx];
+        if !subterms.is_empty() {
+            Self::add(&mut entry.entries, subterms, reference);
+        } else if let Some(reference) = reference
+            && !entry.references.contains(reference)
+        {
+            entry.references.push(reference.clone());
+        }
+    }
+
+    /// Sorts entries (and their subordinate entries) alphabetically, ignoring case
+    fn sort(entries: &mut [IndexEntry]) {
+        entries.sort_by(|a, b| {
+            a.term
+                .to_lowercase()
+                .cmp(&b.term.to_lowercase())
+                .then_with(|| a.term.cmp(&b.term))
+        });
+        for entry in entries.iter_mut() {
+            Self::sort(&mut entry.entries);
+        }
+    }
+}
+
+/// Gathers the index terms as we walk the document tree
+#[derive(Debug)]
+pub struct IndexBuilder {
+    /// IDs already in use, so that generated section IDs are unique
+    ids: HashSet<String>,
+    entries: Vec<IndexEntry>,
+}
+
+impl IndexBuilder {
+    pub fn new(ids: HashSet<String>) -> Self {
+        IndexBuilder {
+            ids,
+            entries: vec![],
+        }
+    }
+
+    /// Generates the index and adds it to any `[index]` sections in the given blocks; sections
+    /// containing index terms are given IDs (e.g., "_big_cats") if they don't have one
+    pub fn build(mut self, blocks: &mut [Block]) {
+        if !has_index_section(blocks) {
+            return;
+        }
+        self.gather(blocks);
+        let categories = self.categories();
+        add_to_index_sections(blocks, &categories);
+    }
+
+    fn gather(&mut self, blocks: &mut [Block]) {
+        for block in blocks.iter_mut() {
+            match block {
+                Block::Section(section) => {
+                    let mut terms = section
+                        .title()
+                        .iter()
+                        .flat_map(index_terms_in)
+                        .collect::<Vec<Vec<String>>>();
+                    for child in section.blocks.iter().filter(|child| !child.is_section()) {
+                        terms.extend(child.inlines().iter().flat_map(index_terms_in));
+                    }
+                    if !terms.is_empty() {
+                        let reference = self.reference_to(section);
+                        for path in terms {
+                            IndexEntry::add(&mut self.entries, &path, Some(&reference));
+                        }
+                    }
+                    self.gather(&mut section.blocks);
+                }
+                // anything not in a section (e.g., the preamble) has nothing to point back to
+                _ => {
+                    for path in block.inlines().iter().flat_map(index_terms_in) {
+                        IndexEntry::add(&mut self.entries, &path, None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Creates a reference to the given section, generating an ID for it if needed
+    fn reference_to(&mut self, section: &mut Section) -> IndexReference {
+        let text = section.title_text();
+        let target = match section.element_id() {
+            Some(id) => id,
+            None => {
+                let id = self.unique_id(&text);
+                section.set_id(id.clone());
+                id
+            }
+        };
+        IndexReference { target, text }
+    }
+
+    /// Generates an ID from the given title as asciidoctor does, e.g., "_big_cats" from "Big
+    /// Cats", appending a number if the ID is already in use
+    fn unique_id(&mut self, title: &str) -> String {
+        let mut base = String::from("_");
+        for c in title.to_lowercase().chars() {
+            if c.is_alphanumeric() || c == '_' {
+                base.push(c)
+            } else if [' ', '.', '-'].contains(&c) && !base.ends_with('_') {
+                base.push('_')
+            }
+        }
+        let base = match base.trim_end_matches('_') {
+            "" => "_section".to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        let mut id = base.clone();
+        let mut count = 1;
+        while self.ids.contains(&id) {
+            count += 1;
+            id = format!("{}_{}", base, count);
+        }
+        self.ids.insert(id.clone());
+        id
+    }
+
+    /// Sorts the gathered entries, grouping them by their (uppercased) first letter; terms that
+    /// don't start with a letter are grouped under "#"
+    fn categories(mut self) -> Vec<IndexCategory> {
+        IndexEntry::sort(&mut self.entries);
+        let mut categories: Vec<IndexCategory> = vec![];
+        for entry in self.entries {
+            let letter = match entry.term.chars().next() {
+                Some(c) if c.is_alphabetic() => c.to_uppercase().to_string(),
+                _ => "#".to_string(),
+            };
+            match categories
+                .iter_mut()
+                .find(|category| category.letter == letter)
+            {
+                Some(category) => category.entries.push(entry),
+                None => categories.push(IndexCategory {
+                    letter,
+                    entries: vec![entry],
+                }),
+            }
+        }
+        // symbols first
+        categories.sort_by_key(|category| category.letter != "#");
+        categories
+    }
+}
+
+/// Finds the index terms in a given inline, including those nested in other inlines
+fn index_terms_in(inline: &Inline) -> Vec<Vec<String>> {
+    match inline {
+        Inline::InlineSpan(span) => match span.index_terms() {
+            Some(terms) => vec![terms],
+            None => span.inlines.iter().flat_map(index_terms_in).collect(),
+        },
+        Inline::InlineRef(iref) => iref.inlines.iter().flat_map(index_terms_in).collect(),
+        _ => vec![],
+    }
+}
+
+fn has_index_section(blocks: &[Block]) -> bool {
+    blocks.iter().any(|block| match block {
+        Block::Section(section) => {
+            section.style() == Some("index") || has_index_section(&section.blocks)
+        }
+        _ => false,
+    })
+}
+
+fn add_to_index_sections(blocks: &mut [Block], categories: &[IndexCategory]) {
+    for block in blocks.iter_mut() {
+        if let Block::Section(section) = block {
+            if section.style() == Some("index") {
+                section.index = categories.to_vec();
+            }
+            add_to_index_sections(&mut section.blocks, categories);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(target: &str) -> IndexReference {
+        IndexReference {
+            target: target.to_string(),
+            text: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn entries_are_nested_and_sorted() {
+        let mut entries: Vec<IndexEntry> = vec![];
+        let terms = |terms: &[&str]| terms.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        IndexEntry::add(&mut entries, &terms(&["lions"]), Some(&reference("b")));
+        IndexEntry::add(
+            &mut entries,
+            &terms(&["Cats", "Big"]),
+            Some(&reference("a")),
+        );
+        IndexEntry::add(
+            &mut entries,
+            &terms(&["Cats", "Big"]),
+            Some(&reference("a")),
+        );
+        IndexEntry::add(
+            &mut entries,
+            &terms(&["Cats", "Big"]),
+            Some(&reference("b")),
+        );
+        IndexEntry::add(&mut entries, &terms(&["Cats"]), None);
+        IndexEntry::sort(&mut entries);
+
+        assert_eq!(
+            entries,
+            vec![
+                IndexEntry {
+                    term: "Cats".into(),
+                    references: vec![],
+                    entries: vec![IndexEntry {
+                        term: "Big".into(),
+                        references: vec![reference("a"), reference("b")],
+                        entries: vec![],
+                    }],
+                },
+                IndexEntry {
+                    term: "lions".into(),
+                    references: vec![reference("b")],
+                    entries: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn categories_group_by_letter() {
+        let mut builder = IndexBuilder::new(HashSet::new());
+        for term in ["cheetahs", "Cats", "2-toed sloths", "lions"] {
+            IndexEntry::add(&mut builder.entries, &[term.to_string()], None);
+        }
+        let categories = builder.categories();
+        assert_eq!(
+            categories
+                .iter()
+                .map(|category| (
+                    category.letter.as_str(),
+                    category.entries.iter().map(|e| e.term.as_str()).collect()
+                ))
+                .collect::<Vec<(&str, Vec<&str>)>>(),
+            vec![
+                ("#", vec!["2-toed sloths"]),
+                ("C", vec!["Cats", "cheetahs"]),
+                ("L", vec!["lions"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_ids_are_unique() {
+        let mut builder = IndexBuilder::new(HashSet::from(["_big_cats".to_string()]));
+        assert_eq!(builder.unique_id("Big Cats"), "_big_cats_2");
+        assert_eq!(builder.unique_id("Big Cats"), "_big_cats_3");
+        assert_eq!(builder.unique_id("Lions, etc."), "_lions_etc");
+    }
+}
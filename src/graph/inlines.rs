@@ -1,6 +1,6 @@
 use core::panic;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Display,
 };
 
@@ -10,9 +10,11 @@ use serde::Serialize;
 use super::{
     metadata::ElementMetadata,
     nodes::{Location, NodeTypes},
-    substitutions::CHARREF_MAP,
+    substitutions::resolve_charref,
 };
 use crate::scanner::tokens::{Token, TokenType};
+use crate::stem::Notation;
+use crate::utils::{line_indentation, percent_encode, reindent_line};
 
 /// Inlines enum containing literals, spans, and references (the latter not implemented)
 #[derive(Serialize, Clone, Debug)]
@@ -111,6 +113,20 @@ impl Inline {
         }
     }
 
+    /// Whether the inline is, or (as the innermost open span) contains, an open code span
+    pub fn in_open_code_span(&self) -> bool {
+        match self {
+            Inline::InlineSpan(span) if span.open => {
+                span.variant == InlineSpanVariant::Code
+                    || span
+                        .inlines
+                        .last()
+                        .is_some_and(|inline| inline.in_open_code_span())
+            }
+            _ => false,
+        }
+    }
+
     /// Used for checking if a given inline is just a literal "\n"
     pub fn is_newline(&self) -> bool {
         match self {
@@ -171,7 +187,27 @@ impl Inline {
                     InlineSpanVariant::Code => "`".to_string(),
                     InlineSpanVariant::Superscript => "^".to_string(),
                     InlineSpanVariant::Subscript => "~".to_string(),
-                    InlineSpanVariant::Footnote => todo!(), // not applicable
+                    // e.g., an unterminated "footnote:disclaimer["
+                    InlineSpanVariant::Footnote => format!(
+                        "footnote:{}[",
+                        span.metadata
+                            .as_ref()
+                            .and_then(|metadata| metadata.attributes.get("footnote_id"))
+                            .map(String::as_str)
+                            .unwrap_or_default()
+                    ),
+                    // macros are closed when scanned, but should one be left open, fall back to
+                    // its opening markup
+                    InlineSpanVariant::Keyboard => "kbd:[".to_string(),
+                    InlineSpanVariant::Button => "btn:[".to_string(),
+                    InlineSpanVariant::Menu => "menu:".to_string(),
+                    InlineSpanVariant::Stem => format!(
+                        "{}:[",
+                        span.stem_notation()
+                            .map(|notation| notation.name())
+                            .unwrap_or("stem")
+                    ),
+                    InlineSpanVariant::IndexTerm => "((".to_string(),
                 };
                 if span.node_form == InlineSpanForm::Unconstrained {
                     literal = literal
@@ -244,17 +280,136 @@ impl Inline {
         }
     }
 
-    pub fn attempt_xref_standardization(&mut self, id_hash: &HashMap<String, Vec<Inline>>) {
-        if let Inline::InlineRef(iref) = self {
-            if matches!(iref.variant, InlineRefVariant::Xref) {
-                if let Some(ref_text) = id_hash.get(&iref.target) {
-                    iref.inlines = ref_text.clone()
-                } else {
-                    warn!("Unable to find xref: {}", iref.target)
+    /// Returns the ID of an inline anchor or a span with an ID, if any
+    pub fn id(&self) -> Option<String> {
+        match self {
+            Inline::InlineRef(iref) if iref.variant == InlineRefVariant::Anchor => {
+                Some(iref.target.clone())
+            }
+            Inline::InlineSpan(span) => span.metadata.as_ref().and_then(|m| m.element_id()),
+            _ => None,
+        }
+    }
+
+    /// Returns any IDs (inline anchors or spans with IDs) and the text xrefs to them should use
+    pub fn id_hashes(&self) -> HashMap<String, Vec<Inline>> {
+        let mut inline_id_hash = HashMap::new();
+        match self {
+            Inline::InlineRef(iref) if iref.variant == InlineRefVariant::Anchor => {
+                let reftext = iref
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.attributes.get("reftext"))
+                    .map(|reftext| {
+                        vec![Inline::InlineLiteral(InlineLiteral::new(
+                            InlineLiteralName::Text,
+                            reftext.clone(),
+                            iref.location.clone(),
+                        ))]
+                    })
+                    .unwrap_or_default();
+                inline_id_hash.insert(iref.target.clone(), reftext);
+            }
+            Inline::InlineSpan(span) => {
+                if let Some(id) = span.metadata.as_ref().and_then(|m| m.element_id()) {
+                    inline_id_hash.insert(id, span.inlines.clone());
                 }
+                for child in span.inlines.iter() {
+                    inline_id_hash.extend(child.id_hashes());
+                }
+            }
+            _ => {}
+        }
+        inline_id_hash
+    }
+
+    /// Resolves an inter-document xref: if the referenced document was included in the parse,
+    /// the xref points to the in-document anchor; otherwise it points to the converted document
+    /// (e.g., "other.adoc#some_id" becomes "other.html#some_id")
+    pub fn resolve_document_xref(&mut self, is_included: &dyn Fn(&str) -> bool, suffix: &str) {
+        if let Inline::InlineRef(iref) = self
+            && let Some(document) = iref.document.take()
+        {
+            if is_included(&document) {
+                return;
+            }
+            let stem = [".adoc", ".asciidoc"]
+                .iter()
+                .find_map(|extension| document.strip_suffix(extension))
+                .unwrap_or(&document);
+            let path = format!("{}{}", stem, suffix);
+            if iref.inlines.is_empty() {
+                iref.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+                    InlineLiteralName::Text,
+                    path.clone(),
+                    iref.location.clone(),
+                )));
+            }
+            iref.target = match iref.target.is_empty() {
+                true => path.clone(),
+                false => format!("{}#{}", path, iref.target),
+            };
+            iref.document = Some(path);
+        }
+    }
+
+    pub fn attempt_xref_standardization(&mut self, id_hash: &HashMap<String, Vec<Inline>>) {
+        if let Inline::InlineRef(iref) = self
+            && matches!(iref.variant, InlineRefVariant::Xref)
+            && iref.inlines.is_empty()
+            && iref.document.is_none()
+        {
+            if let Some(ref_text) = id_hash.get(&iref.target) {
+                iref.inlines = ref_text.clone()
+            } else {
+                warn!("Unable to find xref: {}", iref.target)
             }
         }
     }
+
+    /// The IDs of any named footnotes defined in this inline or its children, e.g., "disclaimer"
+    /// for "footnote:disclaimer[Some text]"
+    pub fn footnote_definition_ids(&self) -> Vec<String> {
+        let mut ids = vec![];
+        if let Inline::InlineSpan(span) = self {
+            if !span.inlines.is_empty()
+                && let Some(id) = span.footnote_id()
+            {
+                ids.push(id);
+            }
+            for child in span.inlines.iter() {
+                ids.extend(child.footnote_definition_ids());
+            }
+        }
+        ids
+    }
+
+    /// Replaces a reference to a named footnote that is never defined, e.g., "footnote:missing[]",
+    /// with its ID as plain text, warning about it
+    pub fn unlink_undefined_footnote(&mut self, defined_ids: &HashSet<String>) {
+        let Inline::InlineSpan(span) = self else {
+            return;
+        };
+        if span.inlines.is_empty()
+            && let Some(id) = span.footnote_id()
+            && !defined_ids.contains(&id)
+        {
+            warn!(
+                "Footnote reference {} has no matching footnote definition, see line {}",
+                id,
+                span.location.first().map_or(0, |location| location.line)
+            );
+            *self = Inline::InlineLiteral(InlineLiteral::new(
+                InlineLiteralName::Text,
+                format!("[{}]", id),
+                span.location.clone(),
+            ));
+            return;
+        }
+        for child in span.inlines.iter_mut() {
+            child.unlink_undefined_footnote(defined_ids);
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -308,11 +463,6 @@ impl InlineSpan {
                 InlineSpanForm::Constrained,
                 token.locations(),
             ),
-            TokenType::Literal => Self::new(
-                InlineSpanVariant::Code,
-                InlineSpanForm::Constrained,
-                token.locations(),
-            ),
             TokenType::Mark => Self::new(
                 InlineSpanVariant::Mark,
                 InlineSpanForm::Constrained,
@@ -344,37 +494,30 @@ impl InlineSpan {
                 InlineSpanForm::Unconstrained,
                 token.locations(),
             ),
-            TokenType::UnconstrainedLiteral => Self::new(
-                InlineSpanVariant::Code,
-                InlineSpanForm::Unconstrained,
-                token.locations(),
-            ),
             TokenType::UnconstrainedMark => Self::new(
                 InlineSpanVariant::Mark,
                 InlineSpanForm::Unconstrained,
                 token.locations(),
             ),
-            TokenType::FootnoteMacro => Self::new(
-                InlineSpanVariant::Footnote,
-                InlineSpanForm::Constrained,
-                token.locations(),
-            ),
-            TokenType::CodeCallout => {
-                let metadata = ElementMetadata::new_with_role("conum".into());
-                let mut code_span = Self::new(
-                    InlineSpanVariant::Strong,
+            TokenType::FootnoteMacro => {
+                let mut footnote = Self::new(
+                    InlineSpanVariant::Footnote,
                     InlineSpanForm::Constrained,
                     token.locations(),
                 );
-                code_span.metadata = Some(metadata);
-                code_span
-                    .inlines
-                    .push(Inline::InlineLiteral(InlineLiteral::new(
-                        InlineLiteralName::Text,
-                        format!("({})", &token.text()[1..token.text().len() - 1]),
-                        token.locations(),
-                    )));
-                code_span
+                // named footnotes, e.g., "footnote:disclaimer[Some text]"
+                let id = token.text();
+                let id = id
+                    .trim_start_matches("footnote:")
+                    .trim_end_matches('[')
+                    .trim();
+                if !id.is_empty() {
+                    footnote.metadata = Some(ElementMetadata::new_with_attribute(
+                        "footnote_id",
+                        id.to_string(),
+                    ));
+                }
+                footnote
             }
             _ => {
                 panic!("Invalid action: tried to create an inline span from an invalid token type")
@@ -382,6 +525,206 @@ impl InlineSpan {
         }
     }
 
+    /// Creates a (closed) span from a keyboard, button, or menu macro, with a literal for each
+    /// key or menu item, e.g., "kbd:[Ctrl+T]" contains "Ctrl" and "T"
+    pub fn new_ui_macro_from_token(token: Token) -> Self {
+        let lexeme = token.text();
+        let (target, content) = lexeme
+            .split_once('[')
+            .map(|(target, content)| (target, content.strip_suffix(']').unwrap_or(content)))
+            .unwrap_or((&lexeme, ""));
+        let (variant, values) = match token.token_type() {
+            TokenType::KeyboardMacro => (InlineSpanVariant::Keyboard, keys(content)),
+            TokenType::ButtonMacro => (InlineSpanVariant::Button, vec![content.to_string()]),
+            TokenType::MenuMacro => {
+                let mut items = vec![target.trim_start_matches("menu:").to_string()];
+                items.extend(
+                    content
+                        .split('>')
+                        .map(|item| item.trim().to_string())
+                        .filter(|item| !item.is_empty()),
+                );
+                (InlineSpanVariant::Menu, items)
+            }
+            _ => panic!("Invalid action: tried to create a UI macro from an invalid token type"),
+        };
+        let mut span = Self::new(variant, InlineSpanForm::Constrained, token.locations());
+        for value in values {
+            span.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+                InlineLiteralName::Text,
+                value,
+                token.locations(),
+            )));
+        }
+        span.open = false;
+        span
+    }
+
+    /// Creates a (closed) span from a STEM macro containing the expression source, noting the
+    /// notation in its metadata; "stem:[]" uses the given (document default) notation
+    pub fn new_stem_from_token(token: Token, default_notation: Notation) -> Self {
+        let lexeme = token.text();
+        let (name, content) = lexeme.split_once(":[").unwrap_or(("stem", &lexeme));
+        let notation = match name {
+            "stem" => default_notation,
+            other => Notation::from_name(other).unwrap_or(default_notation),
+        };
+        let mut span = Self::new(
+            InlineSpanVariant::Stem,
+            InlineSpanForm::Constrained,
+            token.locations(),
+        );
+        span.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+            InlineLiteralName::Text,
+            content
+                .strip_suffix(']')
+                .unwrap_or(content)
+                .replace("\\]", "]"),
+            token.locations(),
+        )));
+        span.metadata = Some(ElementMetadata::new_with_attribute(
+            "notation",
+            notation.name().to_string(),
+        ));
+        span.open = false;
+        span
+    }
+
+    /// Creates a (closed) code callout marker, e.g., "(1)", with its ID and the ID of the callout
+    /// list item it links to
+    pub fn new_code_callout(
+        number: usize,
+        id: String,
+        target: String,
+        location: Vec<Location>,
+    ) -> Self {
+        let mut span = Self::new(
+            InlineSpanVariant::Strong,
+            InlineSpanForm::Constrained,
+            location.clone(),
+        );
+        let mut metadata = ElementMetadata::new_with_id_and_roles(id, vec!["conum".into()]);
+        metadata
+            .attributes
+            .insert("callout_number".into(), number.to_string());
+        metadata.attributes.insert("callout_target".into(), target);
+        span.metadata = Some(metadata);
+        span.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+            InlineLiteralName::Text,
+            format!("({})", number),
+            location,
+        )));
+        span.open = false;
+        span
+    }
+
+    /// For code callout markers, the ID of the callout list item they link to, if any
+    pub fn callout_target(&self) -> Option<&String> {
+        self.metadata
+            .as_ref()
+            .filter(|metadata| metadata.attributes.contains_key("callout_number"))
+            .and_then(|metadata| metadata.attributes.get("callout_target"))
+    }
+
+    /// Unlinks a code callout marker whose callout list item doesn't exist
+    pub fn unlink_callout(&mut self, item_ids: &HashSet<String>) {
+        if let Some(target) = self.callout_target()
+            && !item_ids.contains(target)
+        {
+            warn!(
+                "Callout marker {} has no matching callout list item, see line {}",
+                self.inlines[0].extract_values_to_string(),
+                self.location.first().map_or(0, |location| location.line)
+            );
+            if let Some(metadata) = self.metadata.as_mut() {
+                metadata.attributes.remove("callout_target");
+            }
+        }
+    }
+
+    /// For STEM spans, the notation the expression is written in
+    pub fn stem_notation(&self) -> Option<Notation> {
+        if self.variant != InlineSpanVariant::Stem {
+            return None;
+        }
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("notation"))
+            .and_then(|name| Notation::from_name(name))
+    }
+
+    /// Creates a (closed) span from an index term, noting the primary, secondary, and tertiary
+    /// terms in its metadata; flow terms (e.g., "((Big cats))") also contain the visible text,
+    /// while concealed terms (e.g., "(((Cats, Big)))") contain nothing
+    pub fn new_index_term_from_token(token: Token) -> Self {
+        let lexeme = token.text();
+        let (content, visible) = if let Some(content) = lexeme.strip_prefix("(((") {
+            (content.trim_end_matches(')'), false)
+        } else if let Some(content) = lexeme.strip_prefix("((") {
+            (content.trim_end_matches(')'), true)
+        } else if let Some(content) = lexeme.strip_prefix("indexterm2:[") {
+            (content.trim_end_matches(']'), true)
+        } else {
+            let content = lexeme.strip_prefix("indexterm:[").unwrap_or(&lexeme);
+            (content.trim_end_matches(']'), false)
+        };
+        let mut span = Self::new(
+            InlineSpanVariant::IndexTerm,
+            InlineSpanForm::Constrained,
+            token.locations(),
+        );
+        let terms = match visible {
+            true => vec![content.trim().to_string()],
+            false => index_terms(content),
+        };
+        if visible {
+            span.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+                InlineLiteralName::Text,
+                terms[0].clone(),
+                token.locations(),
+            )));
+        }
+        let mut metadata = ElementMetadata::default();
+        for (name, term) in ["primary", "secondary", "tertiary"].iter().zip(terms) {
+            metadata.attributes.insert(name.to_string(), term);
+        }
+        span.metadata = Some(metadata);
+        span.open = false;
+        span
+    }
+
+    /// For index term spans, the primary term followed by any secondary and tertiary terms
+    pub fn index_terms(&self) -> Option<Vec<String>> {
+        if self.variant != InlineSpanVariant::IndexTerm {
+            return None;
+        }
+        let metadata = self.metadata.as_ref()?;
+        Some(
+            ["primary", "secondary", "tertiary"]
+                .iter()
+                .map_while(|name| metadata.attributes.get(*name).cloned())
+                .collect(),
+        )
+    }
+
+    /// For keyboard, button, and menu spans, the text as it should be displayed, e.g.,
+    /// "Ctrl+T" or "File › Save As"
+    pub fn ui_macro_text(&self) -> Option<String> {
+        let separator = match self.variant {
+            InlineSpanVariant::Keyboard => "+",
+            InlineSpanVariant::Button => "",
+            InlineSpanVariant::Menu => " › ",
+            _ => return None,
+        };
+        Some(
+            self.inlines
+                .iter()
+                .map(|inline| inline.extract_values_to_string())
+                .collect::<Vec<String>>()
+                .join(separator),
+        )
+    }
+
     pub fn add_inline(&mut self, inline: Inline) {
         // update the locations
         self.location = Location::reconcile(self.location.clone(), inline.locations());
@@ -393,11 +736,12 @@ impl InlineSpan {
             }
         } else {
             // combine literals if necessary
-            if matches!(inline, Inline::InlineLiteral(_)) {
-                if let Some(Inline::InlineLiteral(prior_literal)) = self.inlines.last_mut() {
-                    prior_literal.add_text_from_inline_literal(inline);
-                    return;
-                }
+            if let Inline::InlineLiteral(literal) = &inline
+                && let Some(Inline::InlineLiteral(prior_literal)) = self.inlines.last_mut()
+                && prior_literal.subs == literal.subs
+            {
+                prior_literal.add_text_from_inline_literal(inline);
+                return;
             }
             self.inlines.push(inline);
         }
@@ -424,15 +768,16 @@ impl InlineSpan {
                 let mut working_inline = inline.clone();
                 let open_span_literal = working_inline.produce_literal_from_self();
                 let mut inline_children = working_inline.extract_child_inlines();
-                if let Some(inline) = inline_children.front_mut() {
-                    match inline {
-                        Inline::InlineLiteral(literal) => {
-                            literal.prepend_to_value(open_span_literal, literal.location.clone());
-                        }
-                        _ => todo!(),
+                match inline_children.front_mut() {
+                    Some(Inline::InlineLiteral(literal)) => {
+                        literal.prepend_to_value(open_span_literal, literal.location.clone());
                     }
-                } else {
-                    todo!()
+                    // e.g., an email address or another span right after the opening mark
+                    _ => inline_children.push_front(Inline::InlineLiteral(InlineLiteral::new(
+                        InlineLiteralName::Text,
+                        open_span_literal,
+                        working_inline.locations(),
+                    ))),
                 }
                 children.extend(inline_children);
             } else {
@@ -442,6 +787,42 @@ impl InlineSpan {
         children
     }
 
+    /// For named footnotes, e.g., "footnote:disclaimer[Some text]", the footnote ID
+    pub fn footnote_id(&self) -> Option<String> {
+        if self.variant != InlineSpanVariant::Footnote {
+            return None;
+        }
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("footnote_id").cloned())
+    }
+
+    /// Replaces a reused named footnote (e.g., the second "footnote:disclaimer[]") with another
+    /// reference to the existing definition, noting the new reference on the named footnote
+    pub fn deconstruct_footnote_reuse(
+        &self,
+        named_footnote: &mut NamedFootnote,
+        document_id: &str,
+    ) -> Inline {
+        named_footnote.references += 1;
+        let numbering = Inline::InlineLiteral(InlineLiteral::new(
+            InlineLiteralName::Text,
+            format!("{}", named_footnote.number),
+            self.location.clone(),
+        ));
+        let footnote_ref = InlineRef::new_footnote_ref(
+            format!("{}_footnotedef_{}", document_id, named_footnote.number),
+            Some(footnote_ref_id(
+                document_id,
+                named_footnote.number,
+                named_footnote.references,
+            )),
+            numbering,
+            self.location.clone(),
+        );
+        Inline::InlineSpan(InlineSpan::new_footnote_ref(footnote_ref))
+    }
+
     /// Deconstructs a footnote span into the relevant footnote definition ID (to be applied to
     /// the leafblock that contains the footnote text), an InlineSpan `Sup<InlineRef>` that replaces the footnote
     /// with a link to said leafblock, and the vector of inlines that will be inserted into
@@ -453,12 +834,13 @@ impl InlineSpan {
     ) -> (String, Inline, Vec<Inline>) {
         // setup
         let footnote_def_pattern = format!("{}_footnotedef_{}", document_id, count);
-        let footnote_ref_pattern = format!("{}_footnoteref_{}", document_id, count);
+        let footnote_ref_pattern = footnote_ref_id(document_id, count, 1);
         // Literal text for the numbering
         let numbering = Inline::InlineLiteral(InlineLiteral {
             name: InlineLiteralName::Text,
             node_type: NodeTypes::Inline,
             value: format!("{}", count),
+            subs: vec![],
             location: self.location.clone(),
         });
 
@@ -490,6 +872,52 @@ impl InlineSpan {
     }
 }
 
+/// A named footnote (e.g., "footnote:disclaimer[Some text]") whose definition has been
+/// extracted, so that later uses (e.g., "footnote:disclaimer[]") can refer back to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedFootnote {
+    pub number: usize,
+    /// How many times the footnote has been referenced, including its definition
+    pub references: usize,
+}
+
+impl NamedFootnote {
+    pub fn new(number: usize) -> Self {
+        NamedFootnote {
+            number,
+            references: 1,
+        }
+    }
+
+    /// Creates the links from the footnote definition back to each of the references after the
+    /// first, e.g., "_footnoteref_1_2"
+    pub fn back_references(&self, document_id: &str, location: Vec<Location>) -> Vec<Inline> {
+        (2..=self.references)
+            .map(|reference| {
+                Inline::InlineRef(InlineRef::new_footnote_ref(
+                    footnote_ref_id(document_id, self.number, reference),
+                    None,
+                    Inline::InlineLiteral(InlineLiteral::new(
+                        InlineLiteralName::Text,
+                        format!("{}", self.number),
+                        location.clone(),
+                    )),
+                    location.clone(),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// The ID of a given reference to a footnote; the first reference is "_footnoteref_1", and any
+/// later references to the same (named) footnote are "_footnoteref_1_2", etc.
+fn footnote_ref_id(document_id: &str, number: usize, reference: usize) -> String {
+    match reference {
+        1 => format!("{}_footnoteref_{}", document_id, number),
+        _ => format!("{}_footnoteref_{}_{}", document_id, number, reference),
+    }
+}
+
 #[derive(Serialize, PartialEq, Eq, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum InlineSpanVariant {
@@ -500,6 +928,11 @@ pub enum InlineSpanVariant {
     Superscript,
     Subscript,
     Footnote,
+    Keyboard,
+    Button,
+    Menu,
+    Stem,
+    IndexTerm,
 }
 
 #[derive(Serialize, PartialEq, Eq, Clone, Debug)]
@@ -520,6 +953,10 @@ pub struct InlineRef {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<ElementMetadata>,
     location: Vec<Location>,
+    /// for inter-document xrefs, the referenced document: the source (e.g., "other.adoc") until
+    /// resolved, then the converted document (e.g., "other.html")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
 }
 
 impl InlineRef {
@@ -532,6 +969,7 @@ impl InlineRef {
             inlines: vec![],
             metadata: None,
             location,
+            document: None,
         }
     }
 
@@ -555,12 +993,141 @@ impl InlineRef {
             inlines: vec![numbering],
             metadata,
             location,
+            document: None,
         }
     }
 
+    /// Creates an xref from "<<some_id>>", "<<some_id,Some text>>", or
+    /// "xref:some_id[Some text]"; targets with a document part (e.g., "other.adoc#some_id") keep
+    /// the document separately until it is resolved
     pub fn new_xref_from_token(token: Token) -> Self {
-        let target = token.text()[2..token.text().len() - 2].to_string();
-        InlineRef::new(InlineRefVariant::Xref, target, token.locations())
+        let lexeme = token.text();
+        let (target, text) = match lexeme.strip_prefix("xref:") {
+            Some(xref_macro) => {
+                let (target, text) = xref_macro.split_once('[').unwrap_or((xref_macro, "]"));
+                (target, text.trim_end_matches(']'))
+            }
+            None => lexeme[2..lexeme.len() - 2]
+                .split_once(',')
+                .unwrap_or((&lexeme[2..lexeme.len() - 2], "")),
+        };
+        let (document, target) = match target.trim().split_once('#') {
+            Some(("", fragment)) => (None, fragment),
+            Some((document, fragment)) => (Some(document.to_string()), fragment),
+            None if is_asciidoc_path(target.trim()) => (Some(target.trim().to_string()), ""),
+            None => (None, target.trim()),
+        };
+        let mut xref = InlineRef::new(
+            InlineRefVariant::Xref,
+            target.to_string(),
+            token.locations(),
+        );
+        if !text.trim().is_empty() {
+            xref.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+                InlineLiteralName::Text,
+                text.trim().to_string(),
+                token.locations(),
+            )));
+        }
+        xref.document = document;
+        xref
+    }
+
+    /// Creates an anchor from "[[some_id]]", "[[some_id,Reference text]]", or
+    /// "anchor:some_id[Reference text]", keeping any reference text in the metadata
+    pub fn new_anchor_from_token(token: Token) -> Self {
+        let lexeme = token.text();
+        if lexeme.starts_with("[[[") {
+            return Self::new_bibliography_anchor_from_token(token);
+        }
+        let (id, reftext) = match lexeme.strip_prefix("anchor:") {
+            Some(anchor_macro) => {
+                let (id, reftext) = anchor_macro.split_once('[').unwrap_or((anchor_macro, "]"));
+                (id, reftext.trim_end_matches(']'))
+            }
+            None => lexeme[2..lexeme.len() - 2]
+                .split_once(',')
+                .unwrap_or((&lexeme[2..lexeme.len() - 2], "")),
+        };
+        let mut metadata = ElementMetadata::new_inline_with_id(id.trim().to_string());
+        if !reftext.trim().is_empty() {
+            metadata
+                .attributes
+                .insert("reftext".to_string(), reftext.trim().to_string());
+        }
+        let mut anchor = InlineRef::new(
+            InlineRefVariant::Anchor,
+            id.trim().to_string(),
+            token.locations(),
+        );
+        anchor.metadata = Some(metadata);
+        anchor
+    }
+
+    /// Creates a bibliography anchor from "[[[some_ref]]]" or "[[[some_ref,Label]]]", which
+    /// displays (and is cited as) "[Label]", or "[some_ref]" if there is no label
+    pub fn new_bibliography_anchor_from_token(token: Token) -> Self {
+        let lexeme = token.text();
+        let content = &lexeme[3..lexeme.len() - 3];
+        let (id, label) = content.split_once(',').unwrap_or((content, content));
+        let (id, label) = match label.trim() {
+            "" => (id.trim(), id.trim()),
+            label => (id.trim(), label),
+        };
+        let reftext = format!("[{}]", label);
+        let mut metadata = ElementMetadata::new_inline_with_id(id.to_string());
+        metadata
+            .attributes
+            .insert("reftext".to_string(), reftext.clone());
+        metadata.roles.push("bibref".to_string());
+        let mut anchor =
+            InlineRef::new(InlineRefVariant::Anchor, id.to_string(), token.locations());
+        anchor
+            .inlines
+            .push(Inline::InlineLiteral(InlineLiteral::new(
+                InlineLiteralName::Text,
+                reftext,
+                token.locations(),
+            )));
+        anchor.metadata = Some(metadata);
+        anchor
+    }
+
+    /// Creates a "mailto:" link from an email address ("cats@dogs.foo") or a mailto macro
+    /// ("mailto:cats@dogs.foo[Text, Subject, Body]"), percent-encoding any subject and body
+    pub fn new_email_from_token(token: Token) -> Self {
+        let lexeme = token.text();
+        let (address, attributes) = match lexeme.strip_prefix("mailto:") {
+            Some(mailto_macro) => {
+                let (address, attributes) =
+                    mailto_macro.split_once('[').unwrap_or((mailto_macro, "]"));
+                (address, attributes.trim_end_matches(']'))
+            }
+            None => (lexeme.as_str(), ""),
+        };
+        let mut attributes = attributes.splitn(3, ',').map(|value| value.trim());
+        let text = attributes
+            .next()
+            .filter(|text| !text.is_empty())
+            .unwrap_or(address);
+        let query: Vec<String> = ["subject", "body"]
+            .iter()
+            .zip(attributes)
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| format!("{}={}", key, percent_encode(value)))
+            .collect();
+        let mut target = format!("mailto:{}", address);
+        if !query.is_empty() {
+            target.push('?');
+            target.push_str(&query.join("&"));
+        }
+        let mut link = InlineRef::new(InlineRefVariant::Link, target, token.locations());
+        link.inlines.push(Inline::InlineLiteral(InlineLiteral::new(
+            InlineLiteralName::Text,
+            text.to_string(),
+            token.locations(),
+        )));
+        link
     }
 
     pub fn new_link_from_macro_token(token: Token) -> Self {
@@ -588,6 +1155,7 @@ impl InlineRef {
                 inlines: vec![],
                 metadata,
                 location,
+                document: None,
             }
         } else {
             InlineRef::new(InlineRefVariant::Image, target, location)
@@ -611,10 +1179,12 @@ impl InlineRef {
         if let Some(last_inline) = self.inlines.last_mut() {
             match last_inline {
                 Inline::InlineSpan(span) => span.add_inline(inline_literal),
-                Inline::InlineLiteral(prior_literal) => match pass {
+                Inline::InlineLiteral(prior_literal) if prior_literal.subs.is_empty() => match pass
+                {
                     true => prior_literal.pass_text_from_token(&token),
                     false => prior_literal.add_text_from_token(&token),
                 },
+                Inline::InlineLiteral(_) => self.inlines.push(inline_literal),
                 _ => panic!("Can't add text to last token in this context"),
             }
         } else {
@@ -629,6 +1199,7 @@ pub enum InlineRefVariant {
     Link,
     Xref,
     Image,
+    Anchor,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -637,6 +1208,10 @@ pub struct InlineLiteral {
     #[serde(rename = "type")]
     node_type: NodeTypes, // always "string"
     value: String,
+    /// Substitutions that converters still need to apply to the value, e.g., "specialchars" for
+    /// the "<b>" in "+<b>+"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subs: Vec<String>,
     location: Vec<Location>,
 }
 
@@ -646,10 +1221,21 @@ impl InlineLiteral {
             name,
             node_type: NodeTypes::String,
             value,
+            subs: vec![],
             location,
         }
     }
 
+    /// Flags the value as still needing the given substitutions, e.g., "specialchars"
+    pub fn with_subs(mut self, subs: Vec<String>) -> Self {
+        self.subs = subs;
+        self
+    }
+
+    pub fn subs(&self) -> &[String] {
+        &self.subs
+    }
+
     pub fn new_text_from_token(token: &Token) -> Self {
         InlineLiteral::new(InlineLiteralName::Text, token.text(), token.locations())
     }
@@ -658,6 +1244,27 @@ impl InlineLiteral {
         InlineLiteral::new(InlineLiteralName::Charref, token.text(), token.locations())
     }
 
+    /// Inline passthroughs: "+++raw+++" and "$$raw$$" pass their contents through untouched,
+    /// while "+text+" and "++text++" keep their contents flagged for the "specialchars"
+    /// substitution
+    pub fn new_passthrough_from_token(token: &Token) -> Self {
+        let lexeme = token.lexeme.as_str();
+        if let Some(raw) = ["+++", "$$"].iter().find_map(|delimiter| {
+            lexeme
+                .strip_prefix(delimiter)
+                .and_then(|rest| rest.strip_suffix(delimiter))
+        }) {
+            return InlineLiteral::new(InlineLiteralName::Text, raw.to_string(), token.locations());
+        }
+        let delimiter = if lexeme.starts_with("++") { "++" } else { "+" };
+        let text = lexeme
+            .strip_prefix(delimiter)
+            .and_then(|rest| rest.strip_suffix(delimiter))
+            .unwrap_or(lexeme);
+        InlineLiteral::new(InlineLiteralName::Text, text.to_string(), token.locations())
+            .with_subs(vec!["specialchars".to_string()])
+    }
+
     /// Passes the lexeme through, e.g., when we're in a source or literal block
     pub fn new_text_from_token_pass(token: &Token) -> Self {
         InlineLiteral::new(
@@ -686,6 +1293,54 @@ impl InlineLiteral {
         self.location = Location::reconcile(self.location.clone(), token.locations());
     }
 
+    /// The smallest indentation of the non-blank lines starting in this literal, i.e., those
+    /// following a newline, plus the first line if the literal itself starts a line
+    pub fn min_indentation(&self, at_line_start: bool) -> Option<usize> {
+        self.value
+            .split('\n')
+            .enumerate()
+            .filter(|(idx, _)| *idx > 0 || at_line_start)
+            .filter_map(|(_, line)| line_indentation(line))
+            .min()
+    }
+
+    /// Strips `strip` whitespace characters from the lines starting in this literal (see
+    /// [`InlineLiteral::min_indentation`]), then indents them by `indent` spaces; lines that are
+    /// only whitespace are emptied
+    pub fn reindent(&mut self, at_line_start: bool, strip: usize, indent: usize) {
+        self.value = self
+            .value
+            .split('\n')
+            .enumerate()
+            .map(|(idx, line)| match idx > 0 || at_line_start {
+                true => reindent_line(line, strip, indent),
+                false => line.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+
+    /// Checks if the literal ends with a newline, such that whatever follows starts a line
+    pub fn ends_line(&self) -> bool {
+        self.value.ends_with('\n')
+    }
+
+    /// Removes any trailing newlines
+    pub fn trim_trailing_newlines(&mut self) {
+        self.value.truncate(self.value.trim_end_matches('\n').len());
+    }
+
+    /// Removes the line comment "guarding" a code callout, e.g., the "# " in "x = 1  # <1>"
+    pub fn strip_callout_guard(&mut self) {
+        let unspaced = self.value.strip_suffix(' ').unwrap_or(&self.value);
+        for guard in ["//", "#", "--", ";;"] {
+            if let Some(unguarded) = unspaced.strip_suffix(guard) {
+                self.value.truncate(unguarded.len());
+                return;
+            }
+        }
+    }
+
     /// Add test from inline literals; should only really be used in reconciling multi-line spans
     pub fn add_text_from_inline_literal(&mut self, inline: Inline) {
         match inline {
@@ -712,14 +1367,10 @@ impl InlineLiteral {
     }
 
     pub fn value_or_refd_char(&self) -> String {
-        // In HTML these just get passed through, but for certain outputs we want to do replacements. This
-        // is obviously an incomplete table.
+        // In HTML these just get passed through, but for certain outputs we want to do
+        // replacements
         if matches!(self.name, InlineLiteralName::Charref) {
-            let charrefs = CHARREF_MAP.lock().unwrap();
-            match charrefs.get(&self.value) {
-                Some(entity) => entity.to_string(),
-                None => self.value.clone(),
-            }
+            resolve_charref(&self.value).unwrap_or_else(|| self.value.clone())
         } else {
             self.value.clone()
         }
@@ -752,6 +1403,56 @@ impl LineBreak {
     }
 }
 
+/// Splits the contents of a keyboard macro into keys, e.g., "Ctrl+Shift+T" or "Ctrl, T"; a
+/// trailing "+" (as in "Ctrl++") is the plus key
+fn keys(content: &str) -> Vec<String> {
+    let content = content.replace("\\]", "]");
+    let content = content.trim();
+    let delimiter = match content.contains(',') && !content.contains('+') {
+        true => ',',
+        false => '+',
+    };
+    if content.len() <= 1 {
+        return vec![content.to_string()];
+    }
+    let mut keys: Vec<String> = content
+        .split(delimiter)
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .collect();
+    if content.ends_with(delimiter) {
+        keys.push(delimiter.to_string());
+    }
+    keys
+}
+
+/// Splits the contents of a concealed index term into (at most three) terms, e.g.,
+/// `Swords, "Broadsword, two-handed"`, honoring double quotes around terms containing commas
+fn index_terms(content: &str) -> Vec<String> {
+    let mut terms: Vec<String> = vec![];
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in content.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => terms.push(std::mem::take(&mut term)),
+            _ => term.push(c),
+        }
+    }
+    terms.push(term);
+    terms
+        .into_iter()
+        .map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty())
+        .take(3)
+        .collect()
+}
+
+/// Whether a given xref target names an asciidoc document rather than an ID
+fn is_asciidoc_path(target: &str) -> bool {
+    target.ends_with(".adoc") || target.ends_with(".asciidoc")
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -778,6 +1479,221 @@ mod tests {
         assert_eq!(inline.target, "foo".to_string());
     }
 
+    #[rstest]
+    #[case::text("<<foo,Foo text>>", "foo", None, Some("Foo text"))]
+    #[case::document("<<other.adoc#foo>>", "foo", Some("other.adoc"), None)]
+    #[case::document_no_fragment("<<other.adoc>>", "", Some("other.adoc"), None)]
+    #[case::macro_in_document("xref:#foo[]", "foo", None, None)]
+    #[case::macro_document("xref:other#foo[Foo text]", "foo", Some("other"), Some("Foo text"))]
+    fn xref_targets_from_token(
+        #[case] reference: &str,
+        #[case] target: &str,
+        #[case] document: Option<&str>,
+        #[case] text: Option<&str>,
+    ) {
+        let token = Token::new_default(
+            TokenType::CrossReference,
+            reference.to_string(),
+            Some(reference.to_string()),
+            1,
+            1,
+            reference.len(),
+        );
+        let inline = InlineRef::new_xref_from_token(token);
+        assert_eq!(inline.target, target.to_string());
+        assert_eq!(inline.document, document.map(|d| d.to_string()));
+        assert_eq!(
+            inline
+                .inlines
+                .iter()
+                .map(|i| i.extract_values_to_string())
+                .collect::<String>(),
+            text.unwrap_or("")
+        );
+    }
+
+    #[rstest]
+    #[case::single_key("kbd:[F11]", TokenType::KeyboardMacro, vec!["F11"], "F11")]
+    #[case::keys(
+        "kbd:[Ctrl+Shift+T]",
+        TokenType::KeyboardMacro,
+        vec!["Ctrl", "Shift", "T"],
+        "Ctrl+Shift+T"
+    )]
+    #[case::comma_keys("kbd:[Ctrl, T]", TokenType::KeyboardMacro, vec!["Ctrl", "T"], "Ctrl+T")]
+    #[case::plus_key("kbd:[Ctrl++]", TokenType::KeyboardMacro, vec!["Ctrl", "+"], "Ctrl++")]
+    #[case::bracket_key("kbd:[Ctrl+\\]]", TokenType::KeyboardMacro, vec!["Ctrl", "]"], "Ctrl+]")]
+    #[case::button("btn:[Save]", TokenType::ButtonMacro, vec!["Save"], "Save")]
+    #[case::menu(
+        "menu:View[Zoom > Reset]",
+        TokenType::MenuMacro,
+        vec!["View", "Zoom", "Reset"],
+        "View › Zoom › Reset"
+    )]
+    #[case::menu_only("menu:Tools[]", TokenType::MenuMacro, vec!["Tools"], "Tools")]
+    fn ui_macro_from_token(
+        #[case] lexeme: &str,
+        #[case] token_type: TokenType,
+        #[case] values: Vec<&str>,
+        #[case] text: &str,
+    ) {
+        let token = Token::new_default(
+            token_type,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let span = InlineSpan::new_ui_macro_from_token(token);
+        assert!(!span.open);
+        assert_eq!(
+            span.inlines
+                .iter()
+                .map(|i| i.extract_values_to_string())
+                .collect::<Vec<String>>(),
+            values
+        );
+        assert_eq!(span.ui_macro_text(), Some(text.to_string()));
+    }
+
+    #[rstest]
+    #[case::stem_default("stem:[sqrt(4)]", Notation::AsciiMath, "sqrt(4)", Notation::AsciiMath)]
+    #[case::stem_latex_default("stem:[x^2]", Notation::LatexMath, "x^2", Notation::LatexMath)]
+    #[case::latexmath("latexmath:[[a\\]]", Notation::AsciiMath, "[a]", Notation::LatexMath)]
+    #[case::asciimath("asciimath:[x]", Notation::LatexMath, "x", Notation::AsciiMath)]
+    fn stem_from_token(
+        #[case] lexeme: &str,
+        #[case] default_notation: Notation,
+        #[case] source: &str,
+        #[case] notation: Notation,
+    ) {
+        let token = Token::new_default(
+            TokenType::StemMacro,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let span = InlineSpan::new_stem_from_token(token, default_notation);
+        assert!(!span.open);
+        assert_eq!(span.inlines[0].extract_values_to_string(), source);
+        assert_eq!(span.stem_notation(), Some(notation));
+    }
+
+    #[rstest]
+    #[case::flow("((Big cats))", vec!["Big cats"], Some("Big cats"))]
+    #[case::flow_macro("indexterm2:[Big cats]", vec!["Big cats"], Some("Big cats"))]
+    #[case::concealed("(((Cats, Big, Lions)))", vec!["Cats", "Big", "Lions"], None)]
+    #[case::concealed_macro("indexterm:[Cats,Big]", vec!["Cats", "Big"], None)]
+    #[case::quoted(
+        "(((Swords, \"Broadsword, two-handed\")))",
+        vec!["Swords", "Broadsword, two-handed"],
+        None
+    )]
+    fn index_term_from_token(
+        #[case] lexeme: &str,
+        #[case] terms: Vec<&str>,
+        #[case] visible_text: Option<&str>,
+    ) {
+        let token = Token::new_default(
+            TokenType::IndexTerm,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let span = InlineSpan::new_index_term_from_token(token);
+        assert!(!span.open);
+        assert_eq!(
+            span.index_terms(),
+            Some(terms.iter().map(|t| t.to_string()).collect())
+        );
+        assert_eq!(
+            span.inlines
+                .first()
+                .map(|inline| inline.extract_values_to_string()),
+            visible_text.map(|text| text.to_string())
+        );
+    }
+
+    #[rstest]
+    #[case::no_label("[[[pp]]]", "pp", "[pp]")]
+    #[case::label("[[[gof,GoF]]]", "gof", "[GoF]")]
+    #[case::empty_label("[[[gof,]]]", "gof", "[gof]")]
+    fn bibliography_anchor_from_token(#[case] lexeme: &str, #[case] id: &str, #[case] text: &str) {
+        let token = Token::new_default(
+            TokenType::InlineAnchor,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let anchor = Inline::InlineRef(InlineRef::new_anchor_from_token(token));
+        assert_eq!(anchor.id(), Some(id.to_string()));
+        assert_eq!(anchor.extract_values_to_string(), text);
+        assert_eq!(anchor.id_hashes()[id][0].extract_values_to_string(), text);
+    }
+
+    #[rstest]
+    #[case::email("cats@dogs.foo", "mailto:cats@dogs.foo", "cats@dogs.foo")]
+    #[case::mailto("mailto:cats@dogs.foo[]", "mailto:cats@dogs.foo", "cats@dogs.foo")]
+    #[case::mailto_text("mailto:cats@dogs.foo[Cats]", "mailto:cats@dogs.foo", "Cats")]
+    #[case::mailto_subject(
+        "mailto:cats@dogs.foo[Cats, Hi there]",
+        "mailto:cats@dogs.foo?subject=Hi%20there",
+        "Cats"
+    )]
+    #[case::mailto_subject_body(
+        "mailto:cats@dogs.foo[Cats, Hi, Meow & woof, etc.]",
+        "mailto:cats@dogs.foo?subject=Hi&body=Meow%20%26%20woof%2C%20etc.",
+        "Cats"
+    )]
+    fn email_from_token(#[case] lexeme: &str, #[case] target: &str, #[case] text: &str) {
+        let token = Token::new_default(
+            TokenType::Email,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let inline = InlineRef::new_email_from_token(token);
+        assert_eq!(inline.variant, InlineRefVariant::Link);
+        assert_eq!(inline.target, target.to_string());
+        assert_eq!(
+            inline
+                .inlines
+                .iter()
+                .map(|i| i.extract_values_to_string())
+                .collect::<String>(),
+            text
+        );
+    }
+
+    #[rstest]
+    #[case::resolved_elsewhere(false, "other.html#foo")]
+    #[case::included(true, "foo")]
+    fn resolve_document_xref(#[case] included: bool, #[case] expected: &str) {
+        let token = Token::new_default(
+            TokenType::CrossReference,
+            "<<other.adoc#foo>>".to_string(),
+            Some("<<other.adoc#foo>>".to_string()),
+            1,
+            1,
+            18,
+        );
+        let mut inline = Inline::InlineRef(InlineRef::new_xref_from_token(token));
+        inline.resolve_document_xref(&|document| included && document == "other.adoc", ".html");
+        let Inline::InlineRef(iref) = inline else {
+            unreachable!("Expected an InlineRef")
+        };
+        assert_eq!(iref.target, expected.to_string());
+    }
+
     #[test]
     fn image_from_token() {
         let token = Token::new_default(
@@ -817,9 +1733,16 @@ mod tests {
     }
 
     #[rstest]
-    #[case::nbsp("&nbsp;", " ")]
+    #[case::nbsp("&nbsp;", "\u{a0}")]
     #[case::mdash("&mdash;", "—")]
-    #[case::ndash("&ndash;", "—")]
+    #[case::ndash("&ndash;", "–")]
+    #[case::sect("&sect;", "§")]
+    #[case::decimal("&#8212;", "—")]
+    #[case::hex("&#x2014;", "—")]
+    #[case::hex_upper("&#X2014;", "—")]
+    #[case::html5_only("&NotNestedGreaterGreater;", "\u{2aa2}\u{338}")]
+    #[case::unknown("&notathing;", "&notathing;")]
+    #[case::invalid_code_point("&#xD800;", "&#xD800;")]
     #[case::dollar("&dollar;", "$")]
     #[case::amp("&amp;", "&")]
     #[case::gt("&gt;", ">")]
@@ -834,4 +1757,23 @@ mod tests {
         );
         assert_eq!(inline.value_or_refd_char(), replacement)
     }
+
+    #[rstest]
+    #[case::constrained("+<b>+", "<b>", vec!["specialchars"])]
+    #[case::unconstrained("++a & b++", "a & b", vec!["specialchars"])]
+    #[case::triple_plus("+++<b>+++", "<b>", vec![])]
+    #[case::legacy("$$<b>$$", "<b>", vec![])]
+    fn passthrough_from_token(#[case] lexeme: &str, #[case] value: &str, #[case] subs: Vec<&str>) {
+        let token = Token::new_default(
+            TokenType::InlinePassthrough,
+            lexeme.to_string(),
+            Some(lexeme.to_string()),
+            1,
+            1,
+            lexeme.len(),
+        );
+        let literal = InlineLiteral::new_passthrough_from_token(&token);
+        assert_eq!(literal.value, value);
+        assert_eq!(literal.subs, subs)
+    }
 }
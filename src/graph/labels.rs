@@ -0,0 +1,178 @@
+//! Built-in labels (admonition captions, caption prefixes, "Appendix", etc.). Each label can be
+//! set directly with its document attribute (e.g., `:note-caption: Nota Bene`), or localized
+//! with `:lang:`, falling back to English.
+
+use std::collections::HashMap;
+
+/// Document attributes that hold built-in labels
+pub const LABEL_ATTRIBUTES: [&str; 13] = [
+    "appendix-caption",
+    "caution-caption",
+    "chapter-refsig",
+    "details-label",
+    "example-caption",
+    "figure-caption",
+    "important-caption",
+    "note-caption",
+    "part-refsig",
+    "section-refsig",
+    "table-caption",
+    "tip-caption",
+    "warning-caption",
+];
+
+const EN: [(&str, &str); 13] = [
+    ("appendix-caption", "Appendix"),
+    ("caution-caption", "Caution"),
+    ("chapter-refsig", "Chapter"),
+    ("details-label", "Details"),
+    ("example-caption", "Example"),
+    ("figure-caption", "Figure"),
+    ("important-caption", "Important"),
+    ("note-caption", "Note"),
+    ("part-refsig", "Part"),
+    ("section-refsig", "Section"),
+    ("table-caption", "Table"),
+    ("tip-caption", "Tip"),
+    ("warning-caption", "Warning"),
+];
+
+const DE: [(&str, &str); 13] = [
+    ("appendix-caption", "Anhang"),
+    ("caution-caption", "Achtung"),
+    ("chapter-refsig", "Kapitel"),
+    ("details-label", "Details"),
+    ("example-caption", "Beispiel"),
+    ("figure-caption", "Abbildung"),
+    ("important-caption", "Wichtig"),
+    ("note-caption", "Hinweis"),
+    ("part-refsig", "Teil"),
+    ("section-refsig", "Abschnitt"),
+    ("table-caption", "Tabelle"),
+    ("tip-caption", "Tipp"),
+    ("warning-caption", "Warnung"),
+];
+
+const ES: [(&str, &str); 13] = [
+    ("appendix-caption", "Apéndice"),
+    ("caution-caption", "Precaución"),
+    ("chapter-refsig", "Capítulo"),
+    ("details-label", "Detalles"),
+    ("example-caption", "Ejemplo"),
+    ("figure-caption", "Figura"),
+    ("important-caption", "Importante"),
+    ("note-caption", "Nota"),
+    ("part-refsig", "Parte"),
+    ("section-refsig", "Sección"),
+    ("table-caption", "Tabla"),
+    ("tip-caption", "Sugerencia"),
+    ("warning-caption", "Aviso"),
+];
+
+const FR: [(&str, &str); 13] = [
+    ("appendix-caption", "Annexe"),
+    ("caution-caption", "Avertissement"),
+    ("chapter-refsig", "Chapitre"),
+    ("details-label", "Détails"),
+    ("example-caption", "Exemple"),
+    ("figure-caption", "Figure"),
+    ("important-caption", "Important"),
+    ("note-caption", "Note"),
+    ("part-refsig", "Partie"),
+    ("section-refsig", "Section"),
+    ("table-caption", "Tableau"),
+    ("tip-caption", "Astuce"),
+    ("warning-caption", "Attention"),
+];
+
+const JA: [(&str, &str); 13] = [
+    ("appendix-caption", "付録"),
+    ("caution-caption", "注意"),
+    ("chapter-refsig", "章"),
+    ("details-label", "詳細"),
+    ("example-caption", "例"),
+    ("figure-caption", "図"),
+    ("important-caption", "重要"),
+    ("note-caption", "注記"),
+    ("part-refsig", "部"),
+    ("section-refsig", "節"),
+    ("table-caption", "表"),
+    ("tip-caption", "ヒント"),
+    ("warning-caption", "警告"),
+];
+
+/// Returns the label for a given attribute (e.g., "note-caption"), preferring the document
+/// attribute, then the bundled translation for `:lang:`, then English
+pub fn label(attribute: &str, attributes: &HashMap<String, String>) -> Option<String> {
+    if let Some(value) = attributes.get(attribute) {
+        return Some(value.trim().to_string());
+    }
+    let lang = attributes
+        .get("lang")
+        .map(|lang| lang.trim())
+        .unwrap_or("en");
+    bundled_label(lang, attribute)
+        .or_else(|| bundled_label("en", attribute))
+        .map(|label| label.to_string())
+}
+
+/// Returns all built-in labels, keyed by attribute name, for use by the backends
+pub fn labels(attributes: &HashMap<String, String>) -> HashMap<String, String> {
+    LABEL_ATTRIBUTES
+        .iter()
+        .filter_map(|attribute| label(attribute, attributes).map(|l| (attribute.to_string(), l)))
+        .collect()
+}
+
+fn bundled_label(lang: &str, attribute: &str) -> Option<&'static str> {
+    // e.g., "de-AT" falls back to "de"
+    let primary = lang.split(['-', '_']).next().unwrap_or(lang);
+    let catalog = match primary.to_lowercase().as_str() {
+        "en" => EN,
+        "de" => DE,
+        "es" => ES,
+        "fr" => FR,
+        "ja" => JA,
+        _ => return None,
+    };
+    catalog
+        .iter()
+        .find(|(key, _)| *key == attribute)
+        .map(|(_, label)| *label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(None, None, "Note")]
+    #[case(Some("de"), None, "Hinweis")]
+    #[case(Some("ja"), None, "注記")]
+    #[case(Some("de-AT"), None, "Hinweis")]
+    #[case(Some("xx"), None, "Note")]
+    #[case(Some("de"), Some("Nota Bene"), "Nota Bene")]
+    fn note_caption(
+        #[case] lang: Option<&str>,
+        #[case] caption: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let mut attributes = HashMap::new();
+        if let Some(lang) = lang {
+            attributes.insert("lang".to_string(), lang.to_string());
+        }
+        if let Some(caption) = caption {
+            attributes.insert("note-caption".to_string(), caption.to_string());
+        }
+        assert_eq!(
+            label("note-caption", &attributes),
+            Some(expected.to_string())
+        )
+    }
+
+    #[test]
+    fn listing_caption_has_no_default() {
+        assert_eq!(label("listing-caption", &HashMap::new()), None)
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use crate::errors::BlockError;
 use crate::graph::{
-    blocks::{Block, LeafBlock},
-    inlines::Inline,
+    blocks::{Block, extract_footnote},
+    inlines::{Inline, NamedFootnote},
     nodes::{Location, NodeTypes},
 };
 use crate::scanner::tokens::Token;
@@ -34,7 +36,7 @@ impl List {
         match variant {
             ListVariant::Unordered => list_marker.push('*'),
             ListVariant::Ordered => list_marker.push('.'),
-            ListVariant::Callout => todo!(),
+            ListVariant::Callout => list_marker.push_str("<.>"),
         }
 
         List {
@@ -99,6 +101,46 @@ impl ListItem {
         self.principal.push(inline)
     }
 
+    /// Notes a callout list item's number and, if it has a matching marker, its ID and the ID
+    /// of the (first) marker it links back to
+    pub fn set_callout(&mut self, number: usize, link: Option<(String, String)>) {
+        let mut metadata = match link {
+            Some((id, target)) => {
+                let mut metadata = ElementMetadata::new_with_id(id);
+                metadata
+                    .attributes
+                    .insert("callout_target".to_string(), target);
+                metadata
+            }
+            None => ElementMetadata::default(),
+        };
+        metadata
+            .attributes
+            .insert("callout_number".to_string(), number.to_string());
+        self.metadata = Some(metadata);
+    }
+
+    /// For callout list items, their number
+    pub fn callout_number(&self) -> Option<&String> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("callout_number"))
+    }
+
+    /// The list item's ID, if any
+    pub fn id(&self) -> Option<String> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.element_id())
+    }
+
+    /// For callout list items, the ID of the code callout marker they link back to, if any
+    pub fn callout_target(&self) -> Option<&String> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.attributes.get("callout_target"))
+    }
+
     pub fn principal(&self) -> Vec<Inline> {
         self.principal.clone()
     }
@@ -111,32 +153,24 @@ impl ListItem {
         &mut self,
         count: usize,
         document_id: &str,
+        named_footnotes: &mut HashMap<String, NamedFootnote>,
     ) -> Result<Vec<Block>, BlockError> {
         let mut local_count = count;
         let mut extracted: Vec<Block> = Vec::new();
         for idx in 0..self.principal.len() {
             if self.principal[idx].is_footnote() {
-                local_count += 1;
-                let inline_span = self.principal.remove(idx);
-                let Inline::InlineSpan(mut footnote) = inline_span else {
-                    return Err(BlockError::Footnote("Bad is_footnote match".to_string()));
-                };
-                // deconstruct it
-                let (definition_id, replacement_span, footnote_contents) =
-                    footnote.deconstruct_footnote(local_count, document_id);
-                // add the relevant stuff to the return
-                extracted.push(Block::LeafBlock(
-                    LeafBlock::new_footnote_def_from_id_and_inlines(
-                        definition_id,
-                        footnote_contents,
-                    ),
-                ));
-                // put the reference back where the span was
-                self.principal.insert(idx, replacement_span);
+                extracted.extend(extract_footnote(
+                    &mut self.principal,
+                    idx,
+                    &mut local_count,
+                    document_id,
+                    named_footnotes,
+                )?);
             }
         }
         for child in self.blocks.iter_mut() {
-            let child_footnoes = child.extract_footnote_definitions(local_count, document_id)?;
+            let child_footnoes =
+                child.extract_footnote_definitions(local_count, document_id, named_footnotes)?;
             local_count += child_footnoes.len();
             extracted.extend(child_footnoes);
         }
@@ -243,32 +277,24 @@ impl DListItem {
         &mut self,
         count: usize,
         document_id: &str,
+        named_footnotes: &mut HashMap<String, NamedFootnote>,
     ) -> Result<Vec<Block>, BlockError> {
         let mut local_count = count;
         let mut extracted: Vec<Block> = Vec::new();
         for idx in 0..self.principal.len() {
             if self.principal[idx].is_footnote() {
-                local_count += 1;
-                let inline_span = self.principal.remove(idx);
-                let Inline::InlineSpan(mut footnote) = inline_span else {
-                    return Err(BlockError::Footnote("Bad is_footnote match".to_string()));
-                };
-                // deconstruct it
-                let (definition_id, replacement_span, footnote_contents) =
-                    footnote.deconstruct_footnote(local_count, document_id);
-                // add the relevant stuff to the return
-                extracted.push(Block::LeafBlock(
-                    LeafBlock::new_footnote_def_from_id_and_inlines(
-                        definition_id,
-                        footnote_contents,
-                    ),
-                ));
-                // put the reference back where the span was
-                self.principal.insert(idx, replacement_span);
+                extracted.extend(extract_footnote(
+                    &mut self.principal,
+                    idx,
+                    &mut local_count,
+                    document_id,
+                    named_footnotes,
+                )?);
             }
         }
         for child in self.blocks.iter_mut() {
-            let child_footnoes = child.extract_footnote_definitions(local_count, document_id)?;
+            let child_footnoes =
+                child.extract_footnote_definitions(local_count, document_id, named_footnotes)?;
             local_count += child_footnoes.len();
             extracted.extend(child_footnoes);
         }
@@ -11,6 +11,8 @@ use crate::utils::{extract_attributes, key_values_from_named_attribute};
 // just make this quoted, and then pull everything else out
 pub static RE_NAMED_QUOTED: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(\w*=".*?")"#).unwrap());
 pub static RE_NAMED: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(.*?)[=|,](.*)"#).unwrap());
+static RE_LINE_NUMBERS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^\d+((\.\.|-)\d+)?([;\s]+\d+((\.\.|-)\d+)?)*$"#).unwrap());
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum AttributeType {
@@ -77,6 +79,17 @@ impl ElementMetadata {
         }
     }
 
+    pub fn new_with_attribute(name: &str, value: String) -> Self {
+        ElementMetadata {
+            attributes: HashMap::from([(name.to_string(), value)]),
+            options: vec![],
+            roles: vec![],
+            inline_metadata: false,
+            declared_type: None,
+            location: vec![],
+        }
+    }
+
     pub fn new_with_id_and_roles(id: String, roles: Vec<String>) -> Self {
         let mut attrs: HashMap<String, String> = HashMap::with_capacity(1);
         attrs.insert("id".to_string(), id);
@@ -113,14 +126,27 @@ impl ElementMetadata {
             location: token.locations().clone(),
         };
 
-        let class_list = token.lexeme[1..token.lexeme.len() - 1].to_string();
-        new_block_metadata.roles = class_list
-            .split('.')
-            .collect::<Vec<&str>>()
-            .iter_mut()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect();
+        // e.g., "[.role1.role2]", "[#some_id]", or "[#some_id.role]"
+        let shorthand = &token.lexeme[1..token.lexeme.len() - 1];
+        let mut marker = '.';
+        let mut value = String::new();
+        for c in shorthand.chars().chain(['.']) {
+            if ['.', '#'].contains(&c) {
+                if !value.is_empty() {
+                    if marker == '#' {
+                        new_block_metadata
+                            .attributes
+                            .insert("id".to_string(), value.clone());
+                    } else {
+                        new_block_metadata.roles.push(value.clone());
+                    }
+                    value.clear();
+                }
+                marker = c;
+            } else {
+                value.push(c)
+            }
+        }
 
         new_block_metadata
     }
@@ -139,7 +165,7 @@ impl ElementMetadata {
             new_block_metadata.attributes.insert("id".to_string(), id);
         } else {
             let attribute_list = token.lexeme[1..token.lexeme.len() - 1].to_string();
-            new_block_metadata.process_attributes(extract_attributes(&attribute_list));
+            new_block_metadata.process_block_attributes(extract_attributes(&attribute_list));
         }
         new_block_metadata
     }
@@ -152,7 +178,7 @@ impl ElementMetadata {
             }
         } else {
             let attribute_list = token.lexeme[1..token.lexeme.len() - 1].to_string();
-            self.process_attributes(extract_attributes(&attribute_list));
+            self.process_block_attributes(extract_attributes(&attribute_list));
         }
     }
 
@@ -167,22 +193,111 @@ impl ElementMetadata {
         }
         // combine options and roles
         self.options.extend(incoming.options.clone());
-        self.roles.extend(incoming.options.clone());
+        self.roles.extend(incoming.roles.clone());
+    }
+
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|extant| extant == option)
+    }
+
+    /// The block style, e.g., "source" in "[source,rust]" or "sidebar" in "[sidebar]"
+    pub fn block_style(&self) -> Option<&str> {
+        match self.declared_type {
+            Some(AttributeType::Source) => Some("source"),
+            Some(AttributeType::Quote) => Some("quote"),
+            Some(AttributeType::Verse) => Some("verse"),
+            _ => self
+                .attributes
+                .get("positional_1")
+                .map(|style| style.as_str()),
+        }
+    }
+
+    /// The text alignment ("left", "center", "right", or "justify") given by a "text-*" role,
+    /// or, for images, the "align" attribute; the last role given wins
+    pub fn text_alignment(&self) -> Option<&str> {
+        self.roles
+            .iter()
+            .rev()
+            .filter_map(|role| role.strip_prefix("text-"))
+            .find(|alignment| ["left", "center", "right", "justify"].contains(alignment))
+            .or_else(|| {
+                self.attributes
+                    .get("align")
+                    .map(|align| align.as_str())
+                    .filter(|align| ["left", "center", "right"].contains(align))
+            })
+    }
+
+    /// Block attribute lines may lead with a shorthand style, e.g., "[quote.epigraph#intro%unbreakable]"
+    fn process_block_attributes(&mut self, mut attributes: Vec<String>) {
+        if let Some(first) = attributes.first_mut() {
+            *first = self.process_shorthand(first.trim_start());
+        }
+        self.process_attributes(attributes)
+    }
+
+    /// Pulls the roles (".role"), ID ("#id"), and options ("%option") out of the first positional
+    /// attribute, returning the block style that precedes them, if any
+    fn process_shorthand(&mut self, attribute: &str) -> String {
+        if attribute.contains('=') || attribute.starts_with('"') {
+            return attribute.to_string();
+        }
+        let Some(start) = attribute.find(['.', '#', '%']) else {
+            return attribute.to_string();
+        };
+        let mut marker = ' ';
+        let mut value = String::new();
+        for c in attribute[start..].chars().chain(['.']) {
+            if ['.', '#', '%'].contains(&c) {
+                if !value.is_empty() {
+                    match marker {
+                        '#' => {
+                            self.attributes.insert("id".to_string(), value.clone());
+                        }
+                        '%' => self.options.push(value.clone()),
+                        _ => self.roles.push(value.clone()),
+                    }
+                    value.clear();
+                }
+                marker = c;
+            } else {
+                value.push(c)
+            }
+        }
+        attribute[..start].trim_end().to_string()
     }
 
     pub fn process_attributes(&mut self, mut attributes: Vec<String>) {
+        let mut prior_key = String::new();
         for (idx, attribute) in attributes.iter_mut().enumerate() {
+            // attributes are separated by commas and (optionally) spaces, e.g., "640, 360"
+            *attribute = attribute.trim_start().to_string();
+            // an unquoted list of highlighted lines, e.g., "highlight=2..4,7", gets split
+            if prior_key == "highlight" && RE_LINE_NUMBERS.is_match(attribute.trim()) {
+                if let Some(highlight) = self.attributes.get_mut("highlight") {
+                    highlight.push(',');
+                    highlight.push_str(attribute.trim());
+                }
+                continue;
+            }
             match key_values_from_named_attribute(attribute) {
                 Ok((key, values)) => {
                     if key == *"role" {
                         for role in values {
                             self.roles.push(role.to_string());
                         }
+                    } else if key == "opts" || key == "options" {
+                        for option in values.iter().flat_map(|value| value.split(',')) {
+                            self.options.push(option.trim().to_string());
+                        }
                     } else {
-                        self.attributes.insert(key, values.join(" "));
+                        self.attributes.insert(key.clone(), values.join(" "));
                     }
+                    prior_key = key;
                 }
                 Err(_) => {
+                    prior_key.clear();
                     if idx == 0 && attribute.len() >= 2 {
                         match &attribute[..2] {
                             "so" | "qu" | "ve" => {
@@ -205,6 +320,8 @@ impl ElementMetadata {
                 if idx == 1 {
                     self.attributes
                         .insert(String::from("language"), attribute.trim().into());
+                } else if idx == 2 && attribute.trim() == "linenums" {
+                    self.options.push(String::from("linenums"));
                 }
             }
             Some(AttributeType::Quote) | Some(AttributeType::Verse) => {
@@ -267,4 +384,96 @@ mod tests {
             key_values_from_named_attribute("foo=\"bar\"").unwrap()
         )
     }
+
+    #[test]
+    fn source_options_and_highlight() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_block_attributes(extract_attributes(
+            "source%linenums,rust,start=3,highlight=2..4,7",
+        ));
+        assert_eq!(metadata.declared_type, Some(AttributeType::Source));
+        assert!(metadata.has_option("linenums"));
+        assert_eq!(metadata.attributes.get("language").unwrap(), "rust");
+        assert_eq!(metadata.attributes.get("start").unwrap(), "3");
+        assert_eq!(metadata.attributes.get("highlight").unwrap(), "2..4,7");
+    }
+
+    #[test]
+    fn source_linenums_positional() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_attributes(extract_attributes("source,python,linenums"));
+        assert!(metadata.has_option("linenums"));
+        assert_eq!(metadata.attributes.get("language").unwrap(), "python");
+    }
+
+    #[test]
+    fn block_shorthand() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_block_attributes(extract_attributes(
+            "quote.text-center.epigraph#intro%unbreakable, Carl Sandburg, Fog",
+        ));
+        assert_eq!(metadata.declared_type, Some(AttributeType::Quote));
+        assert_eq!(metadata.roles, vec!["text-center", "epigraph"]);
+        assert_eq!(metadata.element_id().unwrap(), "intro");
+        assert!(metadata.has_option("unbreakable"));
+        assert_eq!(
+            metadata.attributes.get("attribution").unwrap(),
+            "Carl Sandburg"
+        );
+        assert_eq!(metadata.attributes.get("citation").unwrap(), "Fog");
+    }
+
+    #[test]
+    fn block_shorthand_role_only() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_block_attributes(extract_attributes(".text-right"));
+        assert_eq!(metadata.roles, vec!["text-right"]);
+        assert!(!metadata.attributes.contains_key("positional_1"));
+    }
+
+    #[test]
+    fn percent_in_alt_text() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_attributes(extract_attributes("Growth of 50% per year"));
+        assert!(metadata.options.is_empty());
+        assert_eq!(
+            metadata.attributes.get("positional_1").unwrap(),
+            "Growth of 50% per year"
+        );
+    }
+
+    #[test]
+    fn percent_in_citation() {
+        let mut metadata = ElementMetadata::default();
+        metadata.process_block_attributes(extract_attributes("quote, Ann, 100% Pure Book"));
+        assert!(metadata.options.is_empty());
+        assert_eq!(metadata.attributes.get("attribution").unwrap(), "Ann");
+        assert_eq!(
+            metadata.attributes.get("citation").unwrap(),
+            "100% Pure Book"
+        );
+    }
+
+    #[test]
+    fn text_alignment_from_roles_and_align() {
+        let mut metadata = ElementMetadata::new_with_role("text-right".into());
+        metadata.roles.push("text-center".into());
+        assert_eq!(metadata.text_alignment(), Some("center"));
+        let mut metadata = ElementMetadata::new_with_attribute("align", "left".into());
+        assert_eq!(metadata.text_alignment(), Some("left"));
+        metadata.roles.push("text-justify".into());
+        assert_eq!(metadata.text_alignment(), Some("justify"));
+        assert_eq!(
+            ElementMetadata::new_with_role("text-muted".into()).text_alignment(),
+            None
+        );
+    }
+
+    #[test]
+    fn named_options() {
+        let mut metadata = ElementMetadata::default();
+        metadata
+            .process_block_attributes(extract_attributes("%header,options=\"autowidth,footer\""));
+        assert_eq!(metadata.options, vec!["header", "autowidth", "footer"]);
+    }
 }
@@ -8,8 +8,11 @@
 
 pub mod asg;
 pub mod blocks;
+pub mod index;
 pub mod inlines;
+pub mod labels;
 pub mod lists;
 pub mod metadata;
 pub mod nodes;
+pub mod numbering;
 pub mod substitutions;
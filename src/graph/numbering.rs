@@ -0,0 +1,418 @@
+//! Section numbering, driven by the document attributes (`sectnums`, `sectnumlevels`,
+//! `partnums`, `xrefstyle`, etc.), applied to the graph once parsing is complete; caption
+//! numbering for figures, tables, examples, and listings, applied as blocks are parsed; and code
+//! callout numbering, which links listing markers and callout list items as they're parsed.
+
+use std::collections::{HashMap, HashSet};
+
+use super::blocks::{Block, Section};
+use super::inlines::{Inline, InlineLiteral, InlineLiteralName};
+use super::labels::label;
+
+/// Section styles that are not numbered unless `:sectnums: all` is set
+const SPECIAL_SECTIONS: [&str; 8] = [
+    "preface",
+    "abstract",
+    "colophon",
+    "dedication",
+    "acknowledgments",
+    "glossary",
+    "bibliography",
+    "index",
+];
+
+/// Keeps track of the section counters as we walk the document tree
+#[derive(Debug)]
+pub struct SectionNumbering {
+    sectnumlevels: usize,
+    all: bool,
+    partnums: bool,
+    xrefstyle: Option<String>,
+    appendix_caption: String,
+    section_refsig: String,
+    chapter_refsig: String,
+    part_refsig: String,
+    book: bool,
+    /// counters for levels 1..=5, indexed by level - 1
+    counts: [usize; 5],
+    part: usize,
+    appendix: usize,
+    /// xref text to apply to the document id hash, keyed by section id
+    pub xref_texts: HashMap<String, Vec<Inline>>,
+}
+
+impl SectionNumbering {
+    pub fn new(attributes: &HashMap<String, String>) -> Self {
+        let get_or = |key: &str, default: &str| {
+            label(key, attributes).unwrap_or_else(|| default.to_string())
+        };
+        SectionNumbering {
+            sectnumlevels: attributes
+                .get("sectnumlevels")
+                .and_then(|levels| levels.trim().parse().ok())
+                .unwrap_or(3),
+            all: attributes
+                .get("sectnums")
+                .is_some_and(|v| v.trim() == "all"),
+            partnums: attributes.contains_key("partnums"),
+            xrefstyle: attributes.get("xrefstyle").map(|s| s.trim().to_string()),
+            appendix_caption: get_or("appendix-caption", "Appendix"),
+            section_refsig: get_or("section-refsig", "Section"),
+            chapter_refsig: get_or("chapter-refsig", "Chapter"),
+            part_refsig: get_or("part-refsig", "Part"),
+            book: attributes.get("doctype").is_some_and(|v| v == "book"),
+            counts: [0; 5],
+            part: 0,
+            appendix: 0,
+            xref_texts: HashMap::new(),
+        }
+    }
+
+    /// Numbers all sections in the given blocks, recursing into subsections
+    pub fn number_blocks(&mut self, blocks: &mut [Block]) {
+        self.number_blocks_in_context(blocks, None, false)
+    }
+
+    fn number_blocks_in_context(
+        &mut self,
+        blocks: &mut [Block],
+        appendix: Option<char>,
+        special: bool,
+    ) {
+        for block in blocks.iter_mut() {
+            let id = block.id();
+            if let Block::Section(section) = block {
+                let style = section.style().map(|s| s.to_string());
+                let mut child_appendix = appendix;
+                let mut child_special = special;
+                match style.as_deref() {
+                    Some("appendix") => {
+                        let letter = self.next_appendix();
+                        section.number = Some(format!("{} {}:", self.appendix_caption, letter));
+                        self.add_xref_text(
+                            &id,
+                            section,
+                            format!("{} {}", self.appendix_caption, letter),
+                        );
+                        child_appendix = Some(letter);
+                    }
+                    Some(style) if SPECIAL_SECTIONS.contains(&style) && !self.all => {
+                        child_special = true;
+                    }
+                    _ if section.level == 0 => {
+                        if self.partnums {
+                            self.part += 1;
+                            let numeral = roman_numeral(self.part);
+                            section.number = Some(format!("{} {}.", self.part_refsig, numeral));
+                            self.add_xref_text(
+                                &id,
+                                section,
+                                format!("{} {}", self.part_refsig, numeral),
+                            );
+                        }
+                    }
+                    _ => {
+                        if !special && section.numbered && section.level <= self.sectnumlevels {
+                            let number = self.next_number(section.level, appendix);
+                            let refsig = if self.book && section.level == 1 {
+                                &self.chapter_refsig
+                            } else {
+                                &self.section_refsig
+                            };
+                            let xref_text = format!("{} {}", refsig, number);
+                            section.number = Some(number);
+                            self.add_xref_text(&id, section, xref_text);
+                        }
+                    }
+                }
+                self.number_blocks_in_context(&mut section.blocks, child_appendix, child_special);
+            }
+        }
+    }
+
+    /// Increments the counter for the given level, resetting any deeper levels, and returns the
+    /// formatted number
+    fn next_number(&mut self, level: usize, appendix: Option<char>) -> String {
+        let index = level.clamp(1, 5) - 1;
+        self.counts[index] += 1;
+        for count in self.counts.iter_mut().skip(index + 1) {
+            *count = 0;
+        }
+        let mut parts: Vec<String> = vec![];
+        // appendix subsections are numbered from the letter, e.g., "A.1"
+        let start = match appendix {
+            Some(letter) => {
+                parts.push(letter.to_string());
+                1
+            }
+            None => 0,
+        };
+        parts.extend(
+            self.counts[start..=index]
+                .iter()
+                .map(|count| count.to_string()),
+        );
+        parts.join(".")
+    }
+
+    fn next_appendix(&mut self) -> char {
+        self.appendix += 1;
+        // appendix subsections start over
+        for count in self.counts.iter_mut().skip(1) {
+            *count = 0;
+        }
+        char::from_u32('A' as u32 + ((self.appendix - 1) % 26) as u32).unwrap_or('A')
+    }
+
+    /// Records the xref text for a numbered section, according to the `xrefstyle`
+    fn add_xref_text(&mut self, id: &Option<String>, section: &Section, signifier: String) {
+        let Some(id) = id else {
+            return;
+        };
+        let literal = |value: String| {
+            Inline::InlineLiteral(InlineLiteral::new(InlineLiteralName::Text, value, vec![]))
+        };
+        let xref_text = match self.xrefstyle.as_deref() {
+            Some("short") => vec![literal(signifier)],
+            Some("full") => {
+                let mut full = vec![literal(format!("{}, “", signifier))];
+                full.extend(section.title());
+                full.push(literal("”".to_string()));
+                full
+            }
+            _ => return,
+        };
+        self.xref_texts.insert(id.clone(), xref_text);
+    }
+}
+
+/// Keeps track of caption numbers (e.g., "Figure 3.") by caption kind
+#[derive(Debug, Default)]
+pub struct CaptionNumbering {
+    counts: HashMap<String, usize>,
+}
+
+impl CaptionNumbering {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a caption to a titled figure, table, example, or listing, honoring an explicit
+    /// `caption=` (or `caption!`) on the block and the relevant `:<kind>-caption:` attribute.
+    /// Listings are only numbered if `:listing-caption:` is set.
+    pub fn caption_block(
+        &mut self,
+        block: &mut Block,
+        attributes: &HashMap<String, String>,
+        unset_attributes: &HashSet<String>,
+    ) {
+        let Some(kind) = block.caption_kind() else {
+            return;
+        };
+        if let Some(metadata) = block.metadata() {
+            if metadata
+                .attributes
+                .iter()
+                .any(|(key, value)| key.starts_with("positional_") && value == "caption!")
+            {
+                return;
+            }
+            if let Some(caption) = metadata.attributes.get("caption") {
+                if !caption.is_empty() {
+                    let caption = caption.trim_end().to_string();
+                    block.set_caption(caption, None);
+                }
+                return;
+            }
+        }
+        let attribute = format!("{}-caption", kind);
+        if unset_attributes.contains(&attribute) {
+            return;
+        }
+        let Some(label) = label(&attribute, attributes).filter(|label| !label.is_empty()) else {
+            return;
+        };
+        let count = self.counts.entry(kind.to_string()).or_default();
+        *count += 1;
+        block.set_caption(
+            format!("{} {}.", label, count),
+            Some(format!("{} {}", label, count)),
+        );
+    }
+}
+
+/// Keeps track of the code callouts (e.g., `<1>` or `<.>`) in the most recent listing, so that
+/// the items of the callout list that follows can be linked to them (and vice versa)
+#[derive(Debug, Default)]
+pub struct CalloutNumbering {
+    /// the number of listings with callouts so far, used to keep IDs unique
+    listings: usize,
+    /// true from the start of a listing until its first callout
+    new_listing: bool,
+    markers: usize,
+    last_number: usize,
+    items: usize,
+    /// the ID of the first marker with a given number in the current listing
+    first_markers: HashMap<usize, String>,
+    /// the IDs of all callout list items linked to a marker
+    pub item_ids: HashSet<String>,
+}
+
+impl CalloutNumbering {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes the start of a listing, which may or may not contain callouts
+    pub fn start_listing(&mut self) {
+        self.new_listing = true;
+    }
+
+    /// Notes the start of a callout list
+    pub fn start_list(&mut self) {
+        self.items = 0;
+    }
+
+    /// Returns the number, ID, and (callout list item) target of a callout marker, e.g., `<2>`;
+    /// `<.>` markers are numbered automatically
+    pub fn next_marker(&mut self, marker: &str) -> (usize, String, String) {
+        if self.new_listing || self.listings == 0 {
+            self.listings += 1;
+            self.new_listing = false;
+            self.markers = 0;
+            self.last_number = 0;
+            self.first_markers.clear();
+        }
+        self.markers += 1;
+        let number = callout_number(marker).unwrap_or(self.last_number + 1);
+        self.last_number = number;
+        let id = format!("co-{}-{}", self.listings, self.markers);
+        self.first_markers.entry(number).or_insert(id.clone());
+        (number, id, format!("callout-{}-{}", self.listings, number))
+    }
+
+    /// Returns the number, ID, and (marker) target of a callout list item, e.g., `<2>`; `<.>`
+    /// items are numbered automatically. Items without a matching marker in the preceding listing
+    /// get neither an ID nor a target.
+    pub fn next_list_item(&mut self, marker: &str) -> (usize, Option<(String, String)>) {
+        self.items += 1;
+        let number = callout_number(marker).unwrap_or(self.items);
+        if self.new_listing {
+            return (number, None);
+        }
+        match self.first_markers.get(&number) {
+            Some(marker_id) => {
+                let id = format!("callout-{}-{}", self.listings, number);
+                self.item_ids.insert(id.clone());
+                (number, Some((id, marker_id.clone())))
+            }
+            None => (number, None),
+        }
+    }
+}
+
+/// The number of a callout marker or list item, e.g., 2 for `<2>` or `<!--2-->`, if it isn't
+/// automatically numbered (`<.>`)
+fn callout_number(marker: &str) -> Option<usize> {
+    marker
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Converts a number to (uppercase) roman numerals
+pub fn roman_numeral(mut value: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut numeral = String::new();
+    for (amount, symbol) in NUMERALS {
+        while value >= amount {
+            numeral.push_str(symbol);
+            value -= amount;
+        }
+    }
+    numeral
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, "I")]
+    #[case(4, "IV")]
+    #[case(9, "IX")]
+    #[case(14, "XIV")]
+    #[case(1994, "MCMXCIV")]
+    fn roman_numerals(#[case] value: usize, #[case] expected: &str) {
+        assert_eq!(roman_numeral(value), expected)
+    }
+
+    #[test]
+    fn numbers_reset_deeper_levels() {
+        let mut numbering = SectionNumbering::new(&HashMap::new());
+        assert_eq!(numbering.next_number(1, None), "1");
+        assert_eq!(numbering.next_number(2, None), "1.1");
+        assert_eq!(numbering.next_number(2, None), "1.2");
+        assert_eq!(numbering.next_number(1, None), "2");
+        assert_eq!(numbering.next_number(2, None), "2.1");
+        let letter = numbering.next_appendix();
+        assert_eq!(numbering.next_number(2, Some(letter)), "A.1");
+    }
+
+    #[test]
+    fn callouts_link_markers_and_items() {
+        let mut numbering = CalloutNumbering::new();
+        numbering.start_listing();
+        assert_eq!(
+            numbering.next_marker("<1>"),
+            (1, "co-1-1".to_string(), "callout-1-1".to_string())
+        );
+        assert_eq!(
+            numbering.next_marker("<1>"),
+            (1, "co-1-2".to_string(), "callout-1-1".to_string())
+        );
+        assert_eq!(
+            numbering.next_marker("<.>"),
+            (2, "co-1-3".to_string(), "callout-1-2".to_string())
+        );
+        numbering.start_list();
+        assert_eq!(
+            numbering.next_list_item("<.> "),
+            (1, Some(("callout-1-1".to_string(), "co-1-1".to_string())))
+        );
+        assert_eq!(
+            numbering.next_list_item("<2> "),
+            (2, Some(("callout-1-2".to_string(), "co-1-3".to_string())))
+        );
+        // an orphan
+        assert_eq!(numbering.next_list_item("<3> "), (3, None));
+        assert_eq!(numbering.item_ids.len(), 2);
+    }
+
+    #[test]
+    fn callout_list_after_listing_without_callouts() {
+        let mut numbering = CalloutNumbering::new();
+        numbering.start_listing();
+        numbering.next_marker("<1>");
+        numbering.start_listing();
+        numbering.start_list();
+        assert_eq!(numbering.next_list_item("<1> "), (1, None));
+    }
+}
@@ -1,51 +1,127 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::sync::Mutex;
-
-pub static CHARREF_MAP: Lazy<Mutex<HashMap<String, &'static str>>> = Lazy::new(|| {
-    let mut m = HashMap::new();
-    m.insert(String::from("&nbsp;"), " ");
-    m.insert(String::from("&#160;"), " ");
-    m.insert(String::from("&mdash;"), "—");
-    m.insert(String::from("&#8212;"), "—");
-    m.insert(String::from("&ndash;"), "—");
-    m.insert(String::from("&dollar;"), "$");
-    m.insert(String::from("&amp;"), "&");
-    m.insert(String::from("&#38;"), "&");
-    m.insert(String::from("&gt;"), ">");
-    m.insert(String::from("&#62;"), ">");
-    m.insert(String::from("&lt;"), "<");
-    m.insert(String::from("&#60;"), "<");
-    m.insert(String::from("&equals;"), "=");
-    m.insert(String::from("&plus;"), "+");
-    m.insert(String::from("&#169;"), "©");
-    m.insert(String::from("&copy;"), "©");
-    m.insert(String::from("&#174;"), "®");
-    m.insert(String::from("&reg;"), "®");
-    m.insert(String::from("&#8482;"), "™");
-    m.insert(String::from("&trade;"), "™");
-    m.insert(String::from("&#8230;"), "…");
-    m.insert(String::from("&#8594;"), "→");
-    m.insert(String::from("&#8658;"), "⇒");
-    m.insert(String::from("&#8592;"), "←");
-    m.insert(String::from("&#8656;"), "⇐");
-    m.insert(String::from("&#sect;"), "§");
-    m.insert(String::from("&#167;"), "§");
-    m.insert(String::from("&euro;"), "€");
-    m.insert(String::from("&#8364;"), "€");
-    m.insert(String::from("&yen;"), "¥");
-    m.insert(String::from("&#165;"), "¥");
-    m.insert(String::from("&quot;"), "\"");
-    m.insert(String::from("&#34;"), "\"");
-    m.insert(String::from("&#39;"), "'");
-    m.insert(String::from("&apos;"), "'");
-    m.insert(String::from("&lsquo;"), "‘");
-    m.insert(String::from("&#8216;"), "‘");
-    m.insert(String::from("&rsquo;"), "’");
-    m.insert(String::from("&#8217;"), "’");
-    m.insert(String::from("&ldquo;"), "“");
-    m.insert(String::from("&#8220;"), "“");
-    m.insert(String::from("&rdquo;"), "”");
-    m.insert(String::from("&#8221;"), "”");
-    Mutex::new(m)
-});
+
+mod entities;
+use entities::NAMED_CHARREFS;
+
+/// Named character references, e.g., "&mdash;", keyed for lookup; built once and read-only
+static CHARREF_MAP: Lazy<HashMap<&'static str, &'static str>> =
+    Lazy::new(|| NAMED_CHARREFS.iter().copied().collect());
+
+/// Returns the character(s) referred to by a named, decimal, or hexadecimal character
+/// reference, e.g., "&mdash;", "&#8212;", or "&#x2014;", or `None` if it isn't valid
+pub fn resolve_charref(charref: &str) -> Option<String> {
+    if let Some(number) = charref
+        .strip_prefix("&#")
+        .and_then(|rest| rest.strip_suffix(';'))
+    {
+        let code_point = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse::<u32>().ok()?,
+        };
+        return char::from_u32(code_point).map(String::from);
+    }
+    CHARREF_MAP
+        .get(charref)
+        .map(|replacement| replacement.to_string())
+}
+
+/// Escapes the characters that are special in HTML/XML, i.e., the "specialchars" substitution
+pub fn escape_special_chars(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The typographic quotes used when `:smart-quotes:` is set, per `:lang:`, e.g., “…” and ‘…’ for
+/// English, „…“ and ‚…‘ for German, or «…» for French
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmartQuotes {
+    open_double: char,
+    close_double: char,
+    open_single: char,
+    close_single: char,
+}
+
+impl SmartQuotes {
+    /// The quotes for a given language tag (e.g., "de" or "zh-TW"), defaulting to English
+    pub fn for_lang(lang: &str) -> Self {
+        let lang = lang.to_lowercase().replace('_', "-");
+        let primary = lang.split('-').next().unwrap_or_default();
+        let (open_double, close_double, open_single, close_single) = match primary {
+            "de" | "cs" | "sk" | "sl" | "bg" | "lt" | "et" | "is" => ('„', '“', '‚', '‘'),
+            "pl" | "hu" | "ro" | "hr" | "sr" => ('„', '”', '‚', '’'),
+            "fr" => ('«', '»', '‹', '›'),
+            "ru" | "uk" | "be" => ('«', '»', '„', '“'),
+            "es" | "it" | "pt" | "ca" | "el" | "nb" | "no" => ('«', '»', '“', '”'),
+            "da" => ('»', '«', '›', '‹'),
+            "sv" | "fi" => ('”', '”', '’', '’'),
+            "ja" => ('「', '」', '『', '』'),
+            "zh" if ["zh-tw", "zh-hk", "zh-hant"]
+                .iter()
+                .any(|tag| lang.starts_with(tag)) =>
+            {
+                ('「', '」', '『', '』')
+            }
+            _ => ('“', '”', '‘', '’'),
+        };
+        SmartQuotes {
+            open_double,
+            close_double,
+            open_single,
+            close_single,
+        }
+    }
+
+    /// Replaces straight quotes and apostrophes in the given text; quotes open at the start of a
+    /// line, after whitespace, or after opening punctuation, given the character prior to the text
+    /// (if any)
+    pub fn apply(&self, text: &str, mut prior: Option<char>) -> String {
+        let mut chars = text.chars().peekable();
+        let mut smartened = String::with_capacity(text.len());
+        while let Some(c) = chars.next() {
+            let next = chars.peek().copied();
+            let opens = prior
+                .is_none_or(|prior| prior.is_whitespace() || "([{—–-/“‘„‚«‹「『".contains(prior));
+            let replacement = match c {
+                '"' if opens => self.open_double,
+                '"' => self.close_double,
+                // e.g., "don't"
+                '\'' if prior.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric) =>
+                {
+                    '’'
+                }
+                '\'' if opens => self.open_single,
+                '\'' => self.close_single,
+                _ => c,
+            };
+            smartened.push(replacement);
+            prior = Some(c);
+        }
+        smartened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::english("en", "She said \"don't\" and 'no'.", "She said “don’t” and ‘no’.")]
+    #[case::german("de-DE", "Er sagte \"Nein\" und 'ja'.", "Er sagte „Nein“ und ‚ja‘.")]
+    #[case::french("fr", "Il a dit \"non\".", "Il a dit «non».")]
+    #[case::japanese("ja", "\"はい\"と言った", "「はい」と言った")]
+    #[case::unknown("xx", "\"Yes\"", "“Yes”")]
+    fn smart_quotes_for_lang(#[case] lang: &str, #[case] text: &str, #[case] expected: &str) {
+        assert_eq!(SmartQuotes::for_lang(lang).apply(text, None), expected)
+    }
+
+    #[test]
+    fn smart_quotes_use_prior_char() {
+        let quotes = SmartQuotes::for_lang("en");
+        assert_eq!(quotes.apply("\" and more", Some('*')), "” and more");
+        assert_eq!(quotes.apply("\"quoted", Some(' ')), "“quoted");
+    }
+}
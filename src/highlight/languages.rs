@@ -0,0 +1,428 @@
+//! The (deliberately small) lexical definitions of the languages the built-in highlighter knows
+use super::Syntax;
+
+const C_LIKE_STRINGS: &[&str] = &["\"", "'"];
+const SLASH_COMMENTS: &[&str] = &["//"];
+const HASH_COMMENTS: &[&str] = &["#"];
+const C_BLOCK_COMMENTS: &[(&str, &str)] = &[("/*", "*/")];
+
+static RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    types: &[
+        "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "str", "u8",
+        "u16", "u32", "u64", "u128", "usize",
+    ],
+    line_comments: SLASH_COMMENTS,
+    block_comments: C_BLOCK_COMMENTS,
+    strings: &["\""],
+    char_literals: true,
+    capitalized_types: true,
+    markup: false,
+    case_insensitive: false,
+};
+
+static PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+    types: &[
+        "bool",
+        "bytes",
+        "dict",
+        "float",
+        "frozenset",
+        "int",
+        "list",
+        "object",
+        "set",
+        "str",
+        "tuple",
+    ],
+    line_comments: HASH_COMMENTS,
+    block_comments: &[],
+    strings: &["\"\"\"", "'''", "\"", "'"],
+    char_literals: false,
+    capitalized_types: true,
+    markup: false,
+    case_insensitive: false,
+};
+
+static JAVASCRIPT: Syntax = Syntax {
+    keywords: &[
+        "abstract",
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "of",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    types: &[
+        "any", "bigint", "boolean", "never", "number", "object", "string", "symbol", "unknown",
+    ],
+    line_comments: SLASH_COMMENTS,
+    block_comments: C_BLOCK_COMMENTS,
+    strings: &["\"", "'", "`"],
+    char_literals: false,
+    capitalized_types: true,
+    markup: false,
+    case_insensitive: false,
+};
+
+static JAVA: Syntax = Syntax {
+    keywords: &[
+        "abstract",
+        "assert",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "do",
+        "else",
+        "enum",
+        "extends",
+        "false",
+        "final",
+        "finally",
+        "for",
+        "if",
+        "implements",
+        "import",
+        "instanceof",
+        "interface",
+        "native",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "record",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "synchronized",
+        "this",
+        "throw",
+        "throws",
+        "true",
+        "try",
+        "var",
+        "void",
+        "volatile",
+        "while",
+    ],
+    types: &[
+        "boolean", "byte", "char", "double", "float", "int", "long", "short",
+    ],
+    line_comments: SLASH_COMMENTS,
+    block_comments: C_BLOCK_COMMENTS,
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: true,
+    markup: false,
+    case_insensitive: false,
+};
+
+static C: Syntax = Syntax {
+    keywords: &[
+        "auto",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "constexpr",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "extern",
+        "false",
+        "for",
+        "goto",
+        "if",
+        "inline",
+        "namespace",
+        "new",
+        "nullptr",
+        "private",
+        "protected",
+        "public",
+        "register",
+        "return",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "typename",
+        "union",
+        "using",
+        "virtual",
+        "volatile",
+        "while",
+    ],
+    types: &[
+        "bool", "char", "double", "float", "int", "long", "short", "signed", "size_t", "unsigned",
+        "void",
+    ],
+    line_comments: SLASH_COMMENTS,
+    block_comments: C_BLOCK_COMMENTS,
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static GO: Syntax = Syntax {
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "false",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "iota",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    types: &[
+        "bool", "byte", "error", "float32", "float64", "int", "int8", "int16", "int32", "int64",
+        "rune", "string", "uint", "uint8", "uint16", "uint32", "uint64", "uintptr",
+    ],
+    line_comments: SLASH_COMMENTS,
+    block_comments: C_BLOCK_COMMENTS,
+    strings: &["\"", "'", "`"],
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static RUBY: Syntax = Syntax {
+    keywords: &[
+        "alias", "and", "begin", "break", "case", "class", "def", "defined", "do", "else", "elsif",
+        "end", "ensure", "false", "for", "if", "in", "module", "next", "nil", "not", "or", "redo",
+        "require", "rescue", "retry", "return", "self", "super", "then", "true", "undef", "unless",
+        "until", "when", "while", "yield",
+    ],
+    types: &[],
+    line_comments: HASH_COMMENTS,
+    block_comments: &[],
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: true,
+    markup: false,
+    case_insensitive: false,
+};
+
+static SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "select", "then", "until", "while",
+    ],
+    types: &[],
+    line_comments: HASH_COMMENTS,
+    block_comments: &[],
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static JSON: Syntax = Syntax {
+    keywords: &["false", "null", "true"],
+    types: &[],
+    line_comments: &[],
+    block_comments: &[],
+    strings: &["\""],
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static YAML: Syntax = Syntax {
+    keywords: &["false", "no", "null", "off", "on", "true", "yes"],
+    types: &[],
+    line_comments: HASH_COMMENTS,
+    block_comments: &[],
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static TOML: Syntax = Syntax {
+    keywords: &["false", "true"],
+    types: &[],
+    line_comments: HASH_COMMENTS,
+    block_comments: &[],
+    strings: &["\"\"\"", "'''", "\"", "'"],
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static SQL: Syntax = Syntax {
+    keywords: &[
+        "add", "all", "alter", "and", "as", "asc", "between", "by", "case", "create", "delete",
+        "desc", "distinct", "drop", "else", "end", "exists", "from", "group", "having", "in",
+        "index", "inner", "insert", "into", "is", "join", "key", "left", "like", "limit", "not",
+        "null", "on", "or", "order", "outer", "primary", "right", "select", "set", "table", "then",
+        "union", "update", "values", "when", "where", "with",
+    ],
+    types: &[
+        "bigint",
+        "boolean",
+        "char",
+        "date",
+        "decimal",
+        "float",
+        "int",
+        "integer",
+        "text",
+        "timestamp",
+        "varchar",
+    ],
+    line_comments: &["--"],
+    block_comments: C_BLOCK_COMMENTS,
+    strings: &["'", "\""],
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: true,
+};
+
+static CSS: Syntax = Syntax {
+    keywords: &["important"],
+    types: &[],
+    line_comments: &[],
+    block_comments: C_BLOCK_COMMENTS,
+    strings: C_LIKE_STRINGS,
+    char_literals: false,
+    capitalized_types: false,
+    markup: false,
+    case_insensitive: false,
+};
+
+static XML: Syntax = Syntax {
+    keywords: &[],
+    types: &[],
+    line_comments: &[],
+    block_comments: &[("<!--", "-->")],
+    strings: &["\""],
+    char_literals: false,
+    capitalized_types: false,
+    markup: true,
+    case_insensitive: false,
+};
+
+/// Returns the syntax for a given `language` attribute value, e.g., "rust" or "py"
+pub fn syntax_for(language: &str) -> Option<&'static Syntax> {
+    match language.trim().to_lowercase().as_str() {
+        "rust" | "rs" => Some(&RUST),
+        "python" | "py" | "python3" => Some(&PYTHON),
+        "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => Some(&JAVASCRIPT),
+        "java" | "kotlin" | "kt" | "csharp" | "cs" | "c#" => Some(&JAVA),
+        "c" | "cpp" | "c++" | "h" | "hpp" => Some(&C),
+        "go" | "golang" => Some(&GO),
+        "ruby" | "rb" => Some(&RUBY),
+        "bash" | "sh" | "shell" | "zsh" | "console" => Some(&SHELL),
+        "json" => Some(&JSON),
+        "yaml" | "yml" => Some(&YAML),
+        "toml" => Some(&TOML),
+        "sql" => Some(&SQL),
+        "css" => Some(&CSS),
+        "xml" | "html" | "svg" | "xhtml" => Some(&XML),
+        _ => None,
+    }
+}
@@ -0,0 +1,426 @@
+//! Built-in, offline syntax highlighting for source blocks, enabled with the
+//! `:source-highlighter:` document attribute.
+//!
+//! This is a lexical highlighter, not a parser: each supported language is described by its
+//! keywords, comment and string delimiters, and so on (see [`languages`]), and source text is
+//! split into classified tokens accordingly. Unknown languages are simply not highlighted.
+pub mod languages;
+
+pub use languages::syntax_for;
+
+/// The names of the `:source-highlighter:` values the built-in highlighter handles; the
+/// server-side highlighters Asciidoctor supports are stood in for by this one
+const BUILTIN_HIGHLIGHTERS: [&str; 4] = ["asciidocr", "rouge", "pygments", "coderay"];
+
+/// Returns true if the `:source-highlighter:` value calls for the built-in highlighter
+pub fn is_builtin_highlighter(name: &str) -> bool {
+    BUILTIN_HIGHLIGHTERS.contains(&name.trim().to_lowercase().as_str())
+}
+
+/// The lexical description of a language
+#[derive(Debug)]
+pub struct Syntax {
+    pub keywords: &'static [&'static str],
+    /// Built-in type names
+    pub types: &'static [&'static str],
+    pub line_comments: &'static [&'static str],
+    /// Opening and closing delimiters
+    pub block_comments: &'static [(&'static str, &'static str)],
+    /// String delimiters, longest first, e.g., `"""` before `"`
+    pub strings: &'static [&'static str],
+    /// Single-quoted character literals, e.g., `'a'`, as distinct from Rust lifetimes
+    pub char_literals: bool,
+    /// Treat capitalized identifiers as type names
+    pub capitalized_types: bool,
+    /// Tag-based languages (HTML, XML), where tag and attribute names are highlighted
+    pub markup: bool,
+    pub case_insensitive: bool,
+}
+
+/// The kinds of highlighted tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Comment,
+}
+
+impl TokenKind {
+    /// The class of the `<span>` wrapping the token in HTML, if any
+    pub fn class_name(&self) -> Option<&'static str> {
+        match self {
+            TokenKind::Plain => None,
+            TokenKind::Keyword => Some("hl-keyword"),
+            TokenKind::Type => Some("hl-type"),
+            TokenKind::Function => Some("hl-function"),
+            TokenKind::String => Some("hl-string"),
+            TokenKind::Number => Some("hl-number"),
+            TokenKind::Comment => Some("hl-comment"),
+        }
+    }
+}
+
+impl Syntax {
+    /// Splits source text into classified tokens; concatenated, the tokens are the source text
+    pub fn tokenize<'a>(&self, source: &'a str) -> Vec<(TokenKind, &'a str)> {
+        let mut tokens: Vec<(TokenKind, &str)> = vec![];
+        let mut plain_start = 0;
+        let mut idx = 0;
+        while let Some(c) = source[idx..].chars().next() {
+            match self.token_at(source, idx) {
+                Some((TokenKind::Plain, len)) => idx += len,
+                Some((kind, len)) => {
+                    if plain_start < idx {
+                        tokens.push((TokenKind::Plain, &source[plain_start..idx]));
+                    }
+                    tokens.push((kind, &source[idx..idx + len]));
+                    idx += len;
+                    plain_start = idx;
+                }
+                None => idx += c.len_utf8(),
+            }
+        }
+        if plain_start < source.len() {
+            tokens.push((TokenKind::Plain, &source[plain_start..]));
+        }
+        tokens
+    }
+
+    /// Highlights source text that's been split into segments (e.g., around code callouts),
+    /// returning the tokens of each segment; tokens may span segments, and are split accordingly
+    pub fn tokenize_segments(&self, segments: &[&str]) -> Vec<Vec<(TokenKind, String)>> {
+        let source: String = segments.concat();
+        let mut tokens = self.tokenize(&source).into_iter();
+        let mut highlighted: Vec<Vec<(TokenKind, String)>> = vec![];
+        // the remainder of a token that continues into the next segment
+        let mut carried: Option<(TokenKind, &str)> = None;
+        for segment in segments {
+            let mut remaining = segment.len();
+            let mut segment_tokens: Vec<(TokenKind, String)> = vec![];
+            while remaining > 0 {
+                let Some((kind, text)) = carried.take().or_else(|| tokens.next()) else {
+                    break;
+                };
+                if text.len() > remaining {
+                    segment_tokens.push((kind, text[..remaining].to_string()));
+                    carried = Some((kind, &text[remaining..]));
+                    remaining = 0;
+                } else {
+                    remaining -= text.len();
+                    segment_tokens.push((kind, text.to_string()));
+                }
+            }
+            highlighted.push(segment_tokens);
+        }
+        highlighted
+    }
+
+    /// The kind and (byte) length of the token starting at the given index, if any
+    fn token_at(&self, source: &str, idx: usize) -> Option<(TokenKind, usize)> {
+        let rest = &source[idx..];
+        let prior = source[..idx].chars().next_back();
+        let c = rest.chars().next()?;
+
+        for (open, close) in self.block_comments {
+            if let Some(comment) = rest.strip_prefix(open) {
+                let len = comment
+                    .find(close)
+                    .map_or(rest.len(), |end| open.len() + end + close.len());
+                return Some((TokenKind::Comment, len));
+            }
+        }
+        for marker in self.line_comments {
+            // "#" also appears in, e.g., shell variables ("$#") or URLs
+            if rest.starts_with(marker)
+                && (*marker != "#" || prior.is_none_or(|prior| prior.is_whitespace()))
+            {
+                return Some((TokenKind::Comment, rest.find('\n').unwrap_or(rest.len())));
+            }
+        }
+        if self.char_literals && c == '\'' {
+            return char_literal_len(rest).map(|len| (TokenKind::String, len));
+        }
+        for delimiter in self.strings {
+            if rest.starts_with(delimiter) {
+                return Some((TokenKind::String, string_len(rest, delimiter)));
+            }
+        }
+        if is_identifier_char(prior) {
+            return None;
+        }
+        if c.is_ascii_digit() {
+            return Some((TokenKind::Number, number_len(rest)));
+        }
+        if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || (self.markup && c == '-')))
+                .unwrap_or(rest.len());
+            return Some((self.identifier_kind(&rest[..len], prior, &rest[len..]), len));
+        }
+        None
+    }
+
+    fn identifier_kind(&self, identifier: &str, prior: Option<char>, after: &str) -> TokenKind {
+        if self.markup {
+            return if matches!(prior, Some('<') | Some('/')) {
+                TokenKind::Keyword
+            } else if after.starts_with('=') {
+                TokenKind::Type
+            } else {
+                TokenKind::Plain
+            };
+        }
+        let matches = |word: &&str| {
+            if self.case_insensitive {
+                word.eq_ignore_ascii_case(identifier)
+            } else {
+                *word == identifier
+            }
+        };
+        if self.keywords.iter().any(matches) {
+            TokenKind::Keyword
+        } else if self.types.iter().any(matches)
+            || (self.capitalized_types && identifier.starts_with(|c: char| c.is_uppercase()))
+        {
+            TokenKind::Type
+        } else if after.starts_with('(') {
+            TokenKind::Function
+        } else {
+            TokenKind::Plain
+        }
+    }
+}
+
+fn is_identifier_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The length of a string, including its delimiters; unterminated strings run to the end of the
+/// line (or, for multi-line delimiters, the end of the source)
+fn string_len(rest: &str, delimiter: &str) -> usize {
+    let multiline = delimiter.len() > 1 || delimiter == "`";
+    let mut chars = rest.char_indices().skip(delimiter.chars().count());
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == '\n' && !multiline {
+            return idx;
+        } else if rest[idx..].starts_with(delimiter) {
+            return idx + delimiter.len();
+        }
+    }
+    rest.len()
+}
+
+/// The length of a character literal, e.g., `'a'` or `'\n'`, if it is one
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    match chars.next()? {
+        (_, '\\') => rest[2..]
+            .find('\'')
+            .filter(|end| *end <= 8)
+            .map(|end| end + 3),
+        (_, '\'' | '\n') => None,
+        _ => chars
+            .next()
+            .filter(|(_, c)| *c == '\'')
+            .map(|(idx, _)| idx + 1),
+    }
+}
+
+/// The length of a number, including any suffixes, hex digits, exponents, etc.
+fn number_len(rest: &str) -> usize {
+    let mut len = 0;
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        let continues = c.is_alphanumeric()
+            || c == '_'
+            || (c == '.' && chars.peek().is_some_and(|next| next.is_ascii_digit()));
+        if !continues {
+            break;
+        }
+        len += c.len_utf8();
+    }
+    len
+}
+
+/// Escapes the characters that are special in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Renders tokens as HTML, wrapping each highlighted token in a classed `<span>`; spans are closed
+/// at the end of each line, so the output can be split into lines safely
+pub fn to_html<S: AsRef<str>>(tokens: &[(TokenKind, S)]) -> String {
+    let mut html = String::new();
+    for (kind, text) in tokens {
+        let text = escape_html(text.as_ref());
+        match kind.class_name() {
+            Some(class_name) => {
+                let lines: Vec<String> = text
+                    .split('\n')
+                    .map(|line| {
+                        if line.is_empty() {
+                            String::new()
+                        } else {
+                            format!("<span class=\"{}\">{}</span>", class_name, line)
+                        }
+                    })
+                    .collect();
+                html.push_str(&lines.join("\n"));
+            }
+            None => html.push_str(&text),
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn highlighted(language: &str, source: &str) -> Vec<(TokenKind, String)> {
+        syntax_for(language)
+            .unwrap()
+            .tokenize(source)
+            .into_iter()
+            .filter(|(kind, _)| *kind != TokenKind::Plain)
+            .map(|(kind, text)| (kind, text.to_string()))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::rust(
+        "rust",
+        "fn main() { let x: u8 = 1; } // done",
+        vec![
+            (TokenKind::Keyword, "fn"),
+            (TokenKind::Function, "main"),
+            (TokenKind::Keyword, "let"),
+            (TokenKind::Type, "u8"),
+            (TokenKind::Number, "1"),
+            (TokenKind::Comment, "// done"),
+        ]
+    )]
+    #[case::rust_lifetime_and_char(
+        "rust",
+        "fn f<'a>(c: &'a str) { 'x' }",
+        vec![
+            (TokenKind::Keyword, "fn"),
+            (TokenKind::Type, "str"),
+            (TokenKind::String, "'x'"),
+        ]
+    )]
+    #[case::python(
+        "py",
+        "def hello(name: str):  # comment\n    \"\"\"Doc\n    string\"\"\"",
+        vec![
+            (TokenKind::Keyword, "def"),
+            (TokenKind::Function, "hello"),
+            (TokenKind::Type, "str"),
+            (TokenKind::Comment, "# comment"),
+            (TokenKind::String, "\"\"\"Doc\n    string\"\"\""),
+        ]
+    )]
+    #[case::escaped_quote(
+        "js",
+        r#"const s = "say \"hi\""; x1 = 0x1F"#,
+        vec![
+            (TokenKind::Keyword, "const"),
+            (TokenKind::String, r#""say \"hi\"""#),
+            (TokenKind::Number, "0x1F"),
+        ]
+    )]
+    #[case::shell_hash(
+        "bash",
+        "echo $# # count",
+        vec![(TokenKind::Comment, "# count")]
+    )]
+    #[case::sql_case_insensitive(
+        "sql",
+        "SELECT id FROM t -- all",
+        vec![
+            (TokenKind::Keyword, "SELECT"),
+            (TokenKind::Keyword, "FROM"),
+            (TokenKind::Comment, "-- all"),
+        ]
+    )]
+    #[case::markup(
+        "html",
+        "<p class=\"x\">Hi</p><!-- c -->",
+        vec![
+            (TokenKind::Keyword, "p"),
+            (TokenKind::Type, "class"),
+            (TokenKind::String, "\"x\""),
+            (TokenKind::Keyword, "p"),
+            (TokenKind::Comment, "<!-- c -->"),
+        ]
+    )]
+    fn tokenizes(
+        #[case] language: &str,
+        #[case] source: &str,
+        #[case] expected: Vec<(TokenKind, &str)>,
+    ) {
+        let expected: Vec<(TokenKind, String)> = expected
+            .into_iter()
+            .map(|(kind, text)| (kind, text.to_string()))
+            .collect();
+        assert_eq!(highlighted(language, source), expected)
+    }
+
+    #[test]
+    fn tokens_cover_source() {
+        let source = "fn main() {\n    println!(\"ünïcödé\"); // ok\n}\n";
+        let tokens = syntax_for("rust").unwrap().tokenize(source);
+        assert_eq!(
+            tokens.iter().map(|(_, text)| *text).collect::<String>(),
+            source
+        )
+    }
+
+    #[test]
+    fn segments_split_tokens() {
+        let segments = ["x = \"a ", "b\" # c", "\ny"];
+        let highlighted = syntax_for("python").unwrap().tokenize_segments(&segments);
+        assert_eq!(
+            highlighted[0],
+            vec![
+                (TokenKind::Plain, "x = ".to_string()),
+                (TokenKind::String, "\"a ".to_string())
+            ]
+        );
+        assert_eq!(
+            highlighted[1],
+            vec![
+                (TokenKind::String, "b\"".to_string()),
+                (TokenKind::Plain, " ".to_string()),
+                (TokenKind::Comment, "# c".to_string())
+            ]
+        );
+        assert_eq!(highlighted[2], vec![(TokenKind::Plain, "\ny".to_string())]);
+    }
+
+    #[test]
+    fn html_spans_close_at_line_ends() {
+        let tokens = syntax_for("c").unwrap().tokenize("/* a\nb */ x < 1");
+        assert_eq!(
+            to_html(&tokens),
+            "<span class=\"hl-comment\">/* a</span>\n<span class=\"hl-comment\">b */</span> x &lt; <span class=\"hl-number\">1</span>"
+        )
+    }
+
+    #[rstest]
+    #[case("asciidocr", true)]
+    #[case("rouge", true)]
+    #[case("highlight.js", false)]
+    fn builtin_highlighters(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(is_builtin_highlighter(name), expected)
+    }
+}
@@ -13,8 +13,7 @@
 //! While eventually the goal is to support the vast majority of the language features, many are
 //! not yet implemented. Notable misses include:
 //!
-//! - Some Asciidoctor document attributes (e.g., `:toc:`, `:icons:`, etc.)
-//! - Indented source blocks
+//! - Some Asciidoctor document attributes (e.g., `:toc:`, etc.)
 //! - Offsets
 //! - Tagged regions
 //! - Conditionals (`ifdef`, `ifndef`, `ifeval`)
@@ -35,6 +34,8 @@
 pub mod backends;
 pub mod errors;
 pub mod graph;
+pub mod highlight;
 pub mod parser;
 pub mod scanner;
+pub mod stem;
 pub mod utils;
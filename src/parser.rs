@@ -1,6 +1,6 @@
 //! Parses a stream of tokens into an [`Asg`] (Abstract Syntax Graph)
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     env,
     fmt::Debug,
     fs,
@@ -18,13 +18,23 @@ use crate::{
 use crate::{
     graph::{
         asg::Asg,
-        blocks::{Block, BlockMacro, Break, LeafBlock, ParentBlock, Section, TableCell},
-        inlines::{Inline, InlineLiteral, InlineLiteralName, InlineRef, InlineSpan, LineBreak},
+        blocks::{
+            Block, BlockMacro, BlockMacroName, Break, LeafBlock, LeafBlockForm, LeafBlockName,
+            ParentBlock, Section, TableCell,
+        },
+        inlines::{
+            Inline, InlineLiteral, InlineLiteralName, InlineRef, InlineSpan, InlineSpanVariant,
+            LineBreak,
+        },
+        labels::labels,
         lists::{DList, DListItem, List, ListItem, ListVariant},
         metadata::{AttributeType, ElementMetadata},
         nodes::{Header, Location},
+        numbering::{CalloutNumbering, CaptionNumbering},
+        substitutions::SmartQuotes,
     },
-    utils::{extract_page_ranges, target_and_attrs_from_token},
+    stem::Notation,
+    utils::{extract_page_ranges, reindent, target_and_attrs_from_token},
 };
 
 /// Parses a stream of tokens into an [`Asg`] (Abstract Syntax Graph), returning the graph once all
@@ -39,6 +49,12 @@ pub struct Parser {
     document_header: Option<Header>,
     /// document-level attributes, used for replacements, etc.
     document_attributes: HashMap<String, String>,
+    /// document-level attributes that have been explicitly unset, e.g., `:figure-caption!:`
+    unset_attributes: HashSet<String>,
+    /// numbers block captions (figures, tables, etc.) in document order
+    caption_numbering: CaptionNumbering,
+    /// numbers and links code callouts and the callout lists that follow them
+    callout_numbering: CalloutNumbering,
     /// holding ground for graph blocks until it's time to push to the main graph
     block_stack: Vec<Block>,
     /// holding ground for inline elements until it's time to push to the relevant block
@@ -46,6 +62,12 @@ pub struct Parser {
     /// holding ground for includes file names; if inside an include push to stack, popping off
     /// once the file's tokens have been accommodated (this allows for simpler nesting)
     file_stack: Vec<String>,
+    /// resolved paths of the asciidoc files included in the parse, used to resolve inter-document
+    /// xrefs to in-document anchors
+    included_files: Vec<PathBuf>,
+    /// file suffix of converted documents, used for inter-document xrefs unless the document sets
+    /// `:relfilesuffix:` or `:outfilesuffix:`
+    outfilesuffix: String,
     /// holding ground for a block title, to be applied to the subsequent block
     block_title: Option<Vec<Inline>>,
     /// holding ground for block metadata, to be applied to the subsequent block
@@ -56,6 +78,17 @@ pub struct Parser {
     /// appends text to block or inline regardless of markup, token, etc. (will need to change
     /// if/when we handle code callouts)
     open_parse_after_as_text_type: Option<TokenType>,
+    /// whether the open verbatim block was opened by an open block delimiter ("--") styled as
+    /// it, e.g., "[source]", in which case that delimiter also closes it
+    open_block_masquerade: bool,
+    /// the substitutions applied to the contents of the open "pass:[]" macro, if any
+    passthrough_subs: PassthroughSubs,
+    /// the inline_stack length when a "pass:[]" macro that applies quotes substitutions (e.g.,
+    /// "pass:q[]") was opened; its contents are parsed as usual until the macro is closed
+    open_passthrough_macro: Option<usize>,
+    /// the last character of the previous token, which decides whether a smart quote opens or
+    /// closes
+    prior_char: Option<char>,
     /// tracks the document heading level offset
     level_offset: i8,
     /// designates whether we're to be adding inlines to the previous block until a newline
@@ -107,9 +140,14 @@ impl Parser {
             last_token_type: TokenType::Eof,
             document_header: None,
             document_attributes: HashMap::new(),
+            unset_attributes: HashSet::new(),
+            caption_numbering: CaptionNumbering::new(),
+            callout_numbering: CalloutNumbering::new(),
             block_stack: vec![],
             inline_stack: VecDeque::new(),
             file_stack: vec![],
+            included_files: vec![],
+            outfilesuffix: ".html".to_string(),
             block_title: None,
             metadata: None,
             open_delimited_block_lines: vec![],
@@ -119,6 +157,10 @@ impl Parser {
             in_block_continuation: false,
             preserve_newline_text: false,
             open_parse_after_as_text_type: None,
+            open_block_masquerade: false,
+            passthrough_subs: PassthroughSubs::default(),
+            open_passthrough_macro: None,
+            prior_char: None,
             force_new_block: false,
             close_parent_after_push: false,
             dangling_newline: None,
@@ -135,6 +177,13 @@ impl Parser {
         test_parser
     }
 
+    /// Sets the file suffix of converted documents (default ".html"), used when rewriting
+    /// inter-document xrefs such as `xref:other.adoc#some_id[]`
+    pub fn with_outfilesuffix(mut self, suffix: &str) -> Self {
+        self.outfilesuffix = suffix.to_string();
+        self
+    }
+
     /// Parses the stream of tokens provided by the [`Scanner`].
     pub fn parse<I>(&mut self, tokens: I) -> Result<Asg, ParserError>
     where
@@ -143,7 +192,8 @@ impl Parser {
         let mut asg = Asg::new();
         for result in tokens {
             match result {
-                Ok(token) => {
+                Ok(mut token) => {
+                    self.demote_masqueraded_delimiter(&mut token);
                     let token_type = token.token_type();
                     self.token_into(token, &mut asg)?;
 
@@ -159,18 +209,36 @@ impl Parser {
         while !self.block_stack.is_empty() {
             self.add_last_block_to_graph(&mut asg)?;
         }
-        // cleanup the final tree locations and xrefs
+        // point inter-document xrefs at included content or the converted documents
+        let suffix = self
+            .document_attributes
+            .get("relfilesuffix")
+            .or(self.document_attributes.get("outfilesuffix"))
+            .map(|suffix| suffix.trim().to_string())
+            .unwrap_or(self.outfilesuffix.clone());
+        asg.resolve_document_xrefs(&|document| self.is_included(document), &suffix);
+        asg.unlink_orphan_callouts(&self.callout_numbering.item_ids);
+        asg.unlink_undefined_footnotes();
+        // number sections over the final tree, then cleanup the final tree locations and xrefs
+        asg.number_sections(&self.document_attributes);
+        asg.generate_index();
+        asg.labels = labels(&self.document_attributes);
         asg.consolidate();
         Ok(asg)
     }
 
-    fn token_into(&mut self, token: Token, asg: &mut Asg) -> Result<(), ParserError> {
+    fn token_into(&mut self, mut token: Token, asg: &mut Asg) -> Result<(), ParserError> {
+        let prior_char = std::mem::replace(&mut self.prior_char, token.lexeme.chars().last());
         // if we are not starting with a document-heading acceptable token, get out
         if self.document_header.is_some() && !token.can_be_in_document_header() {
             self.check_and_move_header(asg)?;
         }
 
         if let Some(token_type) = self.open_parse_after_as_text_type {
+            if self.open_block_masquerade && token.token_type() == TokenType::OpenBlock {
+                token.token_type = token_type;
+                self.open_block_masquerade = false;
+            }
             match token_type {
                 TokenType::QuoteVerseBlock => {
                     if token.token_type() == TokenType::QuoteVerseBlock || token.is_inline() {
@@ -188,7 +256,16 @@ impl Parser {
                     .contains(&token.token_type())
                     {
                         self.open_parse_after_as_text_type = Some(token_type)
-                    } else {
+                    } else if token.token_type() == TokenType::Eof
+                        || (token.token_type() == TokenType::NewLineChar
+                            && self.last_token_type == TokenType::NewLineChar)
+                    {
+                        // unterminated macros end with the paragraph
+                        self.open_parse_after_as_text_type = None;
+                        self.passthrough_subs = PassthroughSubs::default();
+                    } else if !(self.passthrough_subs.attributes
+                        && token.token_type() == TokenType::AttributeReference)
+                    {
                         self.pass_text_through(token)?;
                         return Ok(());
                     }
@@ -199,6 +276,18 @@ impl Parser {
                         return Ok(());
                     }
                 }
+                TokenType::LiteralParagraph => {
+                    // literal paragraphs end at the next blank line
+                    if token.token_type() == TokenType::Eof
+                        || (token.token_type() == TokenType::NewLineChar
+                            && self.last_token_type == TokenType::NewLineChar)
+                    {
+                        self.close_literal_paragraph()?;
+                    } else {
+                        self.pass_text_through(token)?;
+                        return Ok(());
+                    }
+                }
                 TokenType::SourceBlock => {
                     // allow callouts and includes
                     if ![token_type, TokenType::CodeCallout, TokenType::Include]
@@ -210,6 +299,8 @@ impl Parser {
                 }
                 _ => self.open_parse_after_as_text_type = Some(token_type),
             }
+        } else if token.token_type() == TokenType::OpenBlock {
+            self.masquerade_open_block(&mut token);
         }
 
         match token.token_type() {
@@ -240,19 +331,25 @@ impl Parser {
 
             // inlines
             TokenType::NewLineChar => self.parse_new_line_char(token, asg),
-            TokenType::Text => self.parse_text(token),
+            TokenType::Text => {
+                if let Some(smart_quotes) = self.smart_quotes()
+                    && !self.in_code_span()
+                {
+                    token.literal = Some(smart_quotes.apply(&token.text(), prior_char));
+                }
+                self.parse_text(token)
+            }
             TokenType::CharRef => self.parse_charref(token),
+            TokenType::InlinePassthrough => self.parse_inline_passthrough(token),
             TokenType::Strong
             | TokenType::Mark
             | TokenType::Monospace
-            | TokenType::Literal
             | TokenType::Emphasis
             | TokenType::Superscript
             | TokenType::Subscript
             | TokenType::UnconstrainedStrong
             | TokenType::UnconstrainedMark
             | TokenType::UnconstrainedMonospace
-            | TokenType::UnconstrainedLiteral
             | TokenType::UnconstrainedEmphasis => self.parse_inline_span(Inline::InlineSpan(
                 InlineSpan::inline_span_from_token(token),
             )),
@@ -266,6 +363,7 @@ impl Parser {
             // references
             TokenType::AttributeReference => self.parse_attribute_reference(token),
             TokenType::CrossReference => self.parse_cross_reference(token),
+            TokenType::InlineAnchor => self.parse_inline_anchor(token),
             TokenType::Include => self.parse_include(token, asg),
             // we just check for the existence of these; we don't actually process them
             TokenType::StartTag | TokenType::EndTag => {Ok(())}
@@ -315,10 +413,14 @@ impl Parser {
                 self.parse_delimited_leaf_block(token)
             }
             TokenType::SourceBlock => self.parse_delimited_leaf_block(token),
+            TokenType::LiteralParagraph => self.parse_literal_paragraph(token),
             TokenType::CodeCallout => self.parse_code_callout(token),
 
             // block macros
             TokenType::BlockImageMacro => self.parse_block_image(token, asg),
+            TokenType::BlockAudioMacro | TokenType::BlockVideoMacro => {
+                self.parse_block_media(token, asg)
+            }
 
             // lists
             TokenType::UnorderedListItem => self.parse_unordered_list_item(token),
@@ -361,7 +463,12 @@ impl Parser {
                 }
                 Ok(())
             }
-            TokenType::Email => todo!()
+            TokenType::Email => self.parse_email(token),
+            TokenType::KeyboardMacro | TokenType::ButtonMacro | TokenType::MenuMacro => {
+                self.parse_ui_macro(token)
+            }
+            TokenType::StemMacro => self.parse_stem_macro(token),
+            TokenType::IndexTerm => self.parse_index_term(token),
         }
     }
 
@@ -379,6 +486,13 @@ impl Parser {
         if key == &value {
             value = ""
         }
+        // unset attributes, e.g., ":sectnums!:" or ":!sectnums:"
+        if let Some(unset_key) = key.strip_suffix('!').or_else(|| key.strip_prefix('!')) {
+            self.document_attributes.remove(unset_key);
+            self.unset_attributes.insert(unset_key.to_string());
+            return Ok(());
+        }
+        self.unset_attributes.remove(*key);
         match *key {
             "leveloffset" => self.parse_level_offset(value),
             _ => {
@@ -429,11 +543,11 @@ impl Parser {
     /// Handle document header
     fn check_and_move_header(&mut self, asg: &mut Asg) -> Result<(), ParserError> {
         self.add_inlines_to_block_stack()?;
-        if let Some(header) = &mut self.document_header {
-            if !header.is_empty() {
-                header.consolidate();
-                asg.add_header(header.clone(), self.document_attributes.clone())
-            }
+        if let Some(header) = &mut self.document_header
+            && !header.is_empty()
+        {
+            header.consolidate();
+            asg.add_header(header.clone(), self.document_attributes.clone())
         }
 
         self.document_header = None;
@@ -466,9 +580,9 @@ impl Parser {
                 } else if let Some(Block::DListItem(_)) = self.block_stack.last() {
                     self.add_last_list_item_to_list()?;
                 }
-                // clear out any inlines
-                self.in_inline_span = false;
+                // clear out any inlines, falling back to text for any spans left open
                 self.add_inlines_to_block_stack()?;
+                self.in_inline_span = false;
                 // and then force a new block hereafter
                 self.force_new_block = true;
                 if let Some(last_block) = self.block_stack.pop() {
@@ -526,10 +640,15 @@ impl Parser {
         let mut included_tags: Vec<String> = vec![];
         let mut asciidoc_include: bool = false;
         let mut current_tag: Option<String> = None;
+        let mut indent: Option<usize> = None;
+        let mut indented_tokens: Vec<Token> = vec![];
 
         let (target, meta) = target_and_attrs_from_token(&token);
         // check for level offsets in the include
         if let Some(metadata) = meta {
+            if let Some(value) = metadata.attributes.get("indent") {
+                indent = value.parse::<usize>().ok();
+            }
             if let Some(value) = metadata.attributes.get("leveloffset") {
                 match self.parse_level_offset(value) {
                     Ok(_) => {}
@@ -538,11 +657,11 @@ impl Parser {
             }
             if let Some(value) = metadata.attributes.get("lines") {
                 included_lines = extract_page_ranges(value);
-                if let Some(line) = included_lines.last() {
-                    if *line == -1 {
-                        include_to_end = true;
-                        included_lines.pop();
-                    }
+                if let Some(line) = included_lines.last()
+                    && *line == -1
+                {
+                    include_to_end = true;
+                    included_lines.pop();
                 }
             }
             if let Some(value) = metadata.attributes.get("tag") {
@@ -568,11 +687,15 @@ impl Parser {
             asciidoc_include = true;
         }
 
+        if asciidoc_include {
+            self.included_files.push(resolved_target.clone());
+        }
+
         // now parse the file
         for result in Scanner::new_with_stack(&open_file(resolved_target), self.file_stack.clone())
         {
             match result {
-                Ok(token) => {
+                Ok(mut token) => {
                     // lines
                     if !included_lines.is_empty() {
                         if !included_lines.contains(&(token.line as i32)) {
@@ -585,44 +708,52 @@ impl Parser {
                     }
                     // tags
                     if !included_tags.is_empty() {
-                        if token.token_type() == TokenType::StartTag {
-                            if let Some(tag) = token.tag() {
-                                if included_tags.contains(&tag) {
-                                    current_tag = Some(tag);
-                                    continue;
-                                }
-                            }
+                        if token.token_type() == TokenType::StartTag
+                            && let Some(tag) = token.tag()
+                            && included_tags.contains(&tag)
+                        {
+                            current_tag = Some(tag);
+                            continue;
                         }
-                        if token.token_type() == TokenType::EndTag {
-                            if let Some(tag) = token.tag() {
-                                if current_tag == Some(tag) {
-                                    current_tag = None;
-                                    continue;
-                                }
-                            }
+                        if token.token_type() == TokenType::EndTag
+                            && let Some(tag) = token.tag()
+                            && current_tag == Some(tag)
+                        {
+                            current_tag = None;
+                            continue;
                         }
                         if current_tag.is_none() {
                             continue;
                         }
                     }
                     if asciidoc_include {
+                        self.demote_masqueraded_delimiter(&mut token);
                         let token_type = token.token_type();
                         self.token_into(token, asg)?;
                         self.last_token_type = token_type;
                     } else {
-                        // allow EOFs to pass through; otherwise just parse as text
+                        // allow EOFs to pass through; otherwise just pass the text through,
+                        // gathering it first if it's to be reindented
                         if matches!(token.token_type(), TokenType::Eof) {
                             self.last_token_type = TokenType::Eof;
                         } else {
                             self.last_token_type = TokenType::Text;
+                            if indent.is_some() {
+                                indented_tokens.push(token);
+                                continue;
+                            }
                         }
-                        self.parse_text(token)?;
+                        self.pass_text_through(token)?;
                     }
                 }
                 Err(e) => return Err(ParserError::Scanner(e)),
             }
         }
 
+        if let Some(indent) = indent {
+            self.pass_reindented_text_through(&indented_tokens, indent)?;
+        }
+
         // clean up inlines
         self.add_inlines_to_block_stack()?;
 
@@ -635,6 +766,34 @@ impl Parser {
         Ok(())
     }
 
+    /// Passes the text of the given (included) tokens through as a single token, with its common
+    /// indentation replaced by `indent` spaces
+    fn pass_reindented_text_through(
+        &mut self,
+        tokens: &[Token],
+        indent: usize,
+    ) -> Result<(), ParserError> {
+        let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+            return Ok(());
+        };
+        let text = reindent(
+            &tokens
+                .iter()
+                .map(|token| token.lexeme.as_str())
+                .collect::<String>(),
+            indent,
+        );
+        self.pass_text_through(Token {
+            token_type: TokenType::Text,
+            lexeme: text.clone(),
+            literal: Some(text),
+            line: first.line,
+            startcol: first.startcol,
+            endcol: last.endcol,
+            file_stack: first.file_stack.clone(),
+        })
+    }
+
     /// Gathers preceding inlines into the "terms" attribute on DListItem, then adds what follows
     /// as you would for a normal list
     fn parse_description_list_term(&mut self, token: Token) -> Result<(), ParserError> {
@@ -681,28 +840,43 @@ impl Parser {
     fn parse_ordered_list_item(&mut self, token: Token, asg: &mut Asg) -> Result<(), ParserError> {
         // clear any dangling newlines
         self.dangling_newline = None;
-        let list_item = ListItem::new(token.lexeme.clone(), token.locations());
+        let mut list_item = ListItem::new(token.lexeme.clone(), token.locations());
         // if there is an appropriate list, we push this onto the open_blocks so inlines can be
         // added
         if self.block_stack.last().is_some()
             && self.block_stack.last().unwrap().is_ordered_list_item()
         {
             self.add_last_list_item_to_list()?
+        } else if token.token_type() == TokenType::CodeCalloutListItem {
+            // check to see if we ought to "close" the source block (almost always)
+            // TODO source blocks should close themselves, I think.
+            if let Some(block) = self.block_stack.last()
+                && block.is_source_block()
+            {
+                // we need to add this before we create the new list
+                self.add_last_to_block_stack_or_graph(asg)?;
+            }
+            self.callout_numbering.start_list();
+            self.push_block_to_stack(Block::List(List::new(
+                ListVariant::Callout,
+                token.locations().clone(),
+            )))?;
         } else {
             // we need to create the list first
-            let mut list = List::new(ListVariant::Ordered, token.locations().clone());
-            if token.token_type() == TokenType::CodeCalloutListItem {
-                // check to see if we ought to "close" the source block (almost always)
-                // TODO source blocks should close themselves, I think.
-                if let Some(block) = self.block_stack.last() {
-                    if block.is_source_block() {
-                        // we need to add this before we create the new list
-                        self.add_last_to_block_stack_or_graph(asg)?;
-                    }
-                }
-                list.metadata = Some(ElementMetadata::new_with_role("colist".to_string()));
+            self.push_block_to_stack(Block::List(List::new(
+                ListVariant::Ordered,
+                token.locations().clone(),
+            )))?;
+        }
+        if token.token_type() == TokenType::CodeCalloutListItem {
+            let (number, link) = self.callout_numbering.next_list_item(&token.lexeme);
+            list_item.set_callout(number, link);
+            if list_item.callout_target().is_none() {
+                warn!(
+                    "Callout list item <{}> has no matching callout marker, see line {}",
+                    number, token.line
+                );
             }
-            self.push_block_to_stack(Block::List(list))?;
         }
         // either way, add the new list item
         self.push_block_to_stack(Block::ListItem(list_item))
@@ -737,6 +911,11 @@ impl Parser {
             return self.parse_section_headings(token, asg);
         }
 
+        // parts, in books
+        if self.is_book() && token.first_location() != Location::default() {
+            return self.parse_section_headings(token, asg);
+        }
+
         if token.first_location() == Location::default() {
             self.in_block_line = true;
             let mut header = Header::new();
@@ -762,12 +941,10 @@ impl Parser {
         //        self.add_last_to_block_stack_or_graph(asg)
         //    }
         //}
-        // add the new section to the stack
-        self.push_block_to_stack(Block::Section(Section::new(
-            "".to_string(),
-            level,
-            token.first_location(),
-        )))?;
+        // add the new section to the stack, noting whether it should be numbered later
+        let mut section = Section::new("".to_string(), level, token.first_location());
+        section.numbered = self.document_attributes.contains_key("sectnums");
+        self.push_block_to_stack(Block::Section(section))?;
         // let us know we're in a block line
         self.in_block_line = true;
         // clear any dangling newlines, since we don't want these added to the title
@@ -792,9 +969,11 @@ impl Parser {
             }
         };
 
+        // level 0 sections are only allowed as parts in books
+        let min_level = if self.is_book() { 0 } else { 1 };
         match level.try_into() {
             Ok(value) => {
-                if !(1..=4).contains(&value) {
+                if !(min_level..=4).contains(&value) {
                     Err(ParserError::HeadingOffsetError(
                         token.line,
                         self.level_offset,
@@ -810,9 +989,33 @@ impl Parser {
         }
     }
 
+    fn is_book(&self) -> bool {
+        self.document_attributes.get("doctype") == Some(&"book".to_string())
+    }
+
+    /// Admonition paragraphs, e.g., "NOTE: ..." or "[NOTE]", the latter of which may also carry
+    /// attributes (e.g., "[NOTE,caption=Remember]") and style a delimited example block
     fn parse_admonition_para_syntax(&mut self, token: Token) -> Result<(), ParserError> {
-        self.block_stack
-            .push(Block::ParentBlock(ParentBlock::new_from_token(token)?));
+        let mut admonition = ParentBlock::new_from_token(token.clone())?;
+        // clear the dangling newline, e.g., after a block title
+        self.dangling_newline = None;
+        if token.text().starts_with('[') {
+            let mut metadata = ElementMetadata::new_block_meta_from_token(token);
+            metadata.attributes.remove("positional_1");
+            if !metadata.is_empty() {
+                admonition.metadata = Some(metadata);
+            }
+        }
+        if let Some(metadata) = self.metadata.take() {
+            match admonition.metadata.as_mut() {
+                Some(extant) => extant.add_metadata_from_other(&metadata),
+                None => admonition.metadata = Some(metadata),
+            }
+        }
+        if let Some(title) = self.block_title.take() {
+            admonition.title = title;
+        }
+        self.block_stack.push(Block::ParentBlock(admonition));
         self.close_parent_after_push = true;
         Ok(())
     }
@@ -832,7 +1035,11 @@ impl Parser {
                 last_inline.close();
                 self.in_inline_span = false;
                 return Ok(());
-            } else if let Inline::InlineSpan(last_span) = last_inline {
+            } else if let Inline::InlineSpan(last_span) = last_inline
+                && last_span.open
+            {
+                // closed spans, including UI macros (closed as soon as their "]" is read), take
+                // no more inlines
                 if let Some(last_internal_inline) = last_span.inlines.last_mut() {
                     if inline == *last_internal_inline {
                         last_internal_inline.reconcile_locations(inline.locations());
@@ -878,6 +1085,114 @@ impl Parser {
         Ok(())
     }
 
+    fn parse_email(&mut self, token: Token) -> Result<(), ParserError> {
+        // author lines aren't yet supported, so leave any header addresses as text
+        if self.document_header.is_some() {
+            return self.parse_text(token);
+        }
+        let email = Inline::InlineRef(InlineRef::new_email_from_token(token));
+        // e.g., "*me@example.com*"
+        if !self.add_macro_inline(email) {
+            self.close_parent_after_push = true;
+        }
+        Ok(())
+    }
+
+    /// Adds an inline produced by a macro or autolink after any newline that preceded it,
+    /// nesting it in the last span if that span is still open, e.g., "*kbd:[Ctrl+T]*"; returns
+    /// whether it was nested
+    fn add_macro_inline(&mut self, inline: Inline) -> bool {
+        if let Some(newline_token) = self.dangling_newline.take() {
+            self.add_text_to_last_inline(newline_token);
+        }
+        if self.in_inline_span
+            && let Some(Inline::InlineSpan(last_span)) = self.inline_stack.back_mut()
+            && last_span.open
+        {
+            last_span.add_inline(inline);
+            return true;
+        }
+        self.inline_stack.push_back(inline);
+        false
+    }
+
+    /// Keyboard, button, and menu macros are only enabled with the `:experimental:` attribute;
+    /// otherwise they remain text
+    fn parse_ui_macro(&mut self, token: Token) -> Result<(), ParserError> {
+        if !self.document_attributes.contains_key("experimental") {
+            return self.parse_text(token);
+        }
+        let ui_macro = Inline::InlineSpan(InlineSpan::new_ui_macro_from_token(token));
+        if !self.add_macro_inline(ui_macro) {
+            self.in_inline_span = false;
+        }
+        Ok(())
+    }
+
+    fn parse_stem_macro(&mut self, token: Token) -> Result<(), ParserError> {
+        let stem = Inline::InlineSpan(InlineSpan::new_stem_from_token(token, self.stem_notation()));
+        // e.g., "*stem:[x^2]*"
+        if !self.add_macro_inline(stem) {
+            self.in_inline_span = false;
+        }
+        Ok(())
+    }
+
+    fn parse_index_term(&mut self, token: Token) -> Result<(), ParserError> {
+        let index_term = InlineSpan::new_index_term_from_token(token.clone());
+        // terms without a primary value, e.g., "(((  )))", are just text
+        if index_term
+            .index_terms()
+            .is_none_or(|terms| terms.first().is_none_or(String::is_empty))
+        {
+            return self.parse_text(token);
+        }
+        // e.g., "_((Big cats))_"
+        if !self.add_macro_inline(Inline::InlineSpan(index_term)) {
+            self.in_inline_span = false;
+        }
+        Ok(())
+    }
+
+    /// The typographic quotes for the document's `:lang:`, if `:smart-quotes:` is set
+    fn smart_quotes(&self) -> Option<SmartQuotes> {
+        if !self.document_attributes.contains_key("smart-quotes") {
+            return None;
+        }
+        Some(SmartQuotes::for_lang(
+            self.document_attributes
+                .get("lang")
+                .map(String::as_str)
+                .unwrap_or("en"),
+        ))
+    }
+
+    /// Checks if we're adding to an open code span, e.g., "`some code`", possibly nested in
+    /// other open spans
+    fn in_code_span(&self) -> bool {
+        if !self.in_inline_span {
+            return false;
+        }
+        let mut inline = self.inline_stack.back();
+        while let Some(Inline::InlineSpan(span)) = inline
+            && span.open
+        {
+            if span.variant == InlineSpanVariant::Code {
+                return true;
+            }
+            inline = span.inlines.last();
+        }
+        false
+    }
+
+    /// The notation used by "stem:[]" and "[stem]" blocks, set by the `:stem:` attribute
+    fn stem_notation(&self) -> Notation {
+        self.document_attributes
+            .get("stem")
+            .and_then(|name| Notation::from_name(name))
+            .unwrap_or(Notation::AsciiMath)
+    }
+
     fn parse_block_image(&mut self, token: Token, asg: &mut Asg) -> Result<(), ParserError> {
         let (target, metadata) = target_and_attrs_from_token(&token);
         self.check_target(token.line, &target)?;
@@ -891,7 +1206,29 @@ impl Parser {
             image_block.caption = caption.clone();
             self.block_title = None
         }
-        self.add_to_block_stack_or_graph(asg, Block::BlockMacro(image_block))
+        let mut block = Block::BlockMacro(image_block);
+        self.caption_block(&mut block);
+        self.add_to_block_stack_or_graph(asg, block)
+    }
+
+    /// Audio and video blocks, titled (via their caption) like images, but not numbered
+    fn parse_block_media(&mut self, token: Token, asg: &mut Asg) -> Result<(), ParserError> {
+        let (target, metadata) = target_and_attrs_from_token(&token);
+        let name = match token.token_type() {
+            TokenType::BlockAudioMacro => BlockMacroName::Audio,
+            _ => BlockMacroName::Video,
+        };
+        let mut media_block =
+            BlockMacro::new_media_block(name, target, metadata, token.locations());
+        if let Some(metadata) = &self.metadata {
+            media_block = media_block.add_metadata(metadata);
+            self.metadata = None;
+        }
+        if let Some(caption) = &self.block_title {
+            media_block.caption = caption.clone();
+            self.block_title = None
+        }
+        self.add_to_block_stack_or_graph(asg, Block::BlockMacro(media_block))
     }
 
     fn parse_inline_image_macro(&mut self, token: Token) -> Result<(), ParserError> {
@@ -917,13 +1254,27 @@ impl Parser {
     }
 
     fn parse_passthrough_inline_macro(&mut self, token: Token) -> Result<(), ParserError> {
-        self.open_parse_after_as_text_type = Some(token.token_type());
+        self.passthrough_subs = PassthroughSubs::from_macro(&token.lexeme);
+        if self.passthrough_subs.quotes {
+            self.open_passthrough_macro = Some(self.inline_stack.len());
+        } else {
+            self.open_parse_after_as_text_type = Some(token.token_type());
+        }
         Ok(())
     }
 
     fn parse_inline_macro_close(&mut self, token: Token) -> Result<(), ParserError> {
         if let Some(TokenType::PassthroughInlineMacro) = self.open_parse_after_as_text_type {
             self.open_parse_after_as_text_type = None
+        } else if let Some(passthrough_idx) = self.open_passthrough_macro
+            && self
+                .inline_stack
+                .iter()
+                .rposition(|inline| inline.is_macro())
+                .is_none_or(|inline_macro_idx| inline_macro_idx < passthrough_idx)
+        {
+            // the contents have already been parsed, so there's nothing to consolidate
+            self.open_passthrough_macro = None
         } else if let Some(inline_macro_idx) = self
             .inline_stack
             .iter()
@@ -971,24 +1322,67 @@ impl Parser {
     }
 
     fn parse_cross_reference(&mut self, token: Token) -> Result<(), ParserError> {
+        // handle newline tokens prior to the reference
+        if let Some(newline_token) = self.dangling_newline.clone() {
+            self.add_text_to_last_inline(newline_token);
+            self.dangling_newline = None;
+        }
         self.inline_stack
             .push_back(Inline::InlineRef(InlineRef::new_xref_from_token(token)));
         self.close_parent_after_push = true;
         Ok(())
     }
 
+    fn parse_inline_anchor(&mut self, token: Token) -> Result<(), ParserError> {
+        // e.g., "`data[[i, j]]`" is code, not an anchor
+        if self
+            .inline_stack
+            .back()
+            .is_some_and(|inline| inline.in_open_code_span())
+        {
+            return self.parse_text(token);
+        }
+        // bibliography anchors, e.g., "* [[[pp]]] ...", only count in bibliography sections or lists
+        if token.text().starts_with("[[[") && !self.in_bibliography() {
+            return self.parse_text(token);
+        }
+        // handle newline tokens prior to the reference
+        if let Some(newline_token) = self.dangling_newline.clone() {
+            self.add_text_to_last_inline(newline_token);
+            self.dangling_newline = None;
+        }
+        self.inline_stack
+            .push_back(Inline::InlineRef(InlineRef::new_anchor_from_token(token)));
+        self.close_parent_after_push = true;
+        Ok(())
+    }
+
+    /// Whether the current block is within a "[bibliography]" section or list
+    fn in_bibliography(&self) -> bool {
+        self.block_stack.iter().any(|block| {
+            block
+                .metadata()
+                .and_then(|metadata| metadata.attributes.get("positional_1"))
+                .is_some_and(|style| style == "bibliography")
+        })
+    }
+
+    /// Code callouts, e.g., "<1>", are only recognized in listings; the line comment "guarding"
+    /// the callout, if any, is removed
     fn parse_code_callout(&mut self, token: Token) -> Result<(), ParserError> {
-        if let Some(value) = self.document_attributes.get("icons") {
-            if value == "true" {
-                if let Some(_last_inline) = self.inline_stack.back_mut() {
-                    // handle deleting comment markup, IF we're handling icons
-                    todo!();
-                }
-            }
+        if self.open_parse_after_as_text_type != Some(TokenType::SourceBlock) {
+            return self.parse_text(token);
+        }
+        if let Some(Inline::InlineLiteral(last_literal)) = self.inline_stack.back_mut() {
+            last_literal.strip_callout_guard();
         }
+        let (number, id, target) = self.callout_numbering.next_marker(&token.text());
         self.inline_stack
-            .push_back(Inline::InlineSpan(InlineSpan::inline_span_from_token(
-                token,
+            .push_back(Inline::InlineSpan(InlineSpan::new_code_callout(
+                number,
+                id,
+                target,
+                token.locations(),
             )));
         Ok(())
     }
@@ -1027,7 +1421,8 @@ impl Parser {
                 self.dangling_newline = None;
                 // add the new text separately
                 self.inline_stack.push_back(Inline::InlineLiteral(
-                    InlineLiteral::new_text_from_token_pass(&token),
+                    InlineLiteral::new_text_from_token_pass(&token)
+                        .with_subs(self.passed_text_subs()),
                 ));
                 return Ok(());
             } else {
@@ -1039,8 +1434,32 @@ impl Parser {
         Ok(())
     }
 
+    /// The substitutions that converters still need to apply to passed-through text, e.g.,
+    /// "specialchars" for the "<b>" in "pass:c[<b>]"
+    fn passed_text_subs(&self) -> Vec<String> {
+        match self.open_parse_after_as_text_type {
+            Some(TokenType::PassthroughInlineMacro) if self.passthrough_subs.specialchars => {
+                vec!["specialchars".to_string()]
+            }
+            _ => vec![],
+        }
+    }
+
     fn parse_charref(&mut self, token: Token) -> Result<(), ParserError> {
         let inline_literal = Inline::InlineLiteral(InlineLiteral::new_charref_from_token(&token));
+        self.add_inline_literal(inline_literal)
+    }
+
+    /// Inline passthroughs, e.g., "+<b>+" or "+++<b>+++", become text literals that are then
+    /// treated like CharRefs
+    fn parse_inline_passthrough(&mut self, token: Token) -> Result<(), ParserError> {
+        let inline_literal =
+            Inline::InlineLiteral(InlineLiteral::new_passthrough_from_token(&token));
+        self.add_inline_literal(inline_literal)
+    }
+
+    /// Adds a stand-alone literal to the last inline, if it's open, or to the inline stack
+    fn add_inline_literal(&mut self, inline_literal: Inline) -> Result<(), ParserError> {
         if let Some(newline_token) = self.dangling_newline.clone() {
             if self.preserve_newline_text {
                 // add the newline as such
@@ -1060,7 +1479,7 @@ impl Parser {
         if let Some(last_inline) = self.inline_stack.back_mut() {
             match last_inline {
                 Inline::InlineSpan(span) => {
-                    if self.in_inline_span {
+                    if self.in_inline_span && span.open {
                         span.add_inline(inline_literal);
                     } else {
                         self.inline_stack.push_back(inline_literal)
@@ -1084,12 +1503,52 @@ impl Parser {
         Ok(())
     }
 
+    /// Indented paragraphs become literal blocks, e.g., "  Some literal text"
+    fn parse_literal_paragraph(&mut self, token: Token) -> Result<(), ParserError> {
+        // table cell contents aren't parsed as blocks
+        if self.block_stack.iter().any(|block| block.is_table()) {
+            return self.parse_text(token);
+        }
+        let mut block = LeafBlock::new(
+            LeafBlockName::Literal,
+            LeafBlockForm::Indented,
+            None,
+            token.locations(),
+            vec![],
+        );
+        if let Some(title) = self.block_title.take() {
+            block.title = title;
+        }
+        self.push_block_to_stack(Block::LeafBlock(block))?;
+        self.force_new_block = false;
+        self.open_parse_after_as_text_type = Some(token.token_type());
+        // keep the indentation, which is stripped when the paragraph is closed
+        self.pass_text_through(token)
+    }
+
+    fn close_literal_paragraph(&mut self) -> Result<(), ParserError> {
+        self.add_inlines_to_block_stack()?;
+        self.open_parse_after_as_text_type = None;
+        if let Some(Block::LeafBlock(leaf)) = self.block_stack.last_mut() {
+            leaf.finish_literal_paragraph();
+        }
+        Ok(())
+    }
+
     fn parse_delimited_leaf_block(&mut self, token: Token) -> Result<(), ParserError> {
         if self.open_parse_after_as_text_type.is_some() {
             // ensure inlines are added appropriately
             self.add_inlines_to_block_stack()?;
             match self.block_stack.pop() {
                 Some(mut open_leaf) => {
+                    if let Block::LeafBlock(leaf) = &mut open_leaf {
+                        if matches!(leaf.name, LeafBlockName::Listing | LeafBlockName::Literal)
+                            && let Some(indent) = leaf.indent()
+                        {
+                            leaf.reindent(indent);
+                        }
+                        leaf.set_source_lines();
+                    }
                     open_leaf.add_locations(token.locations().clone());
                     self.push_block_to_stack(open_leaf)?;
                     self.open_parse_after_as_text_type = None;
@@ -1098,8 +1557,15 @@ impl Parser {
                 None => Err(ParserError::OpenParse(token.line)),
             }
         } else {
+            if token.token_type() == TokenType::SourceBlock {
+                self.callout_numbering.start_listing();
+            }
             self.open_parse_after_as_text_type = Some(token.token_type());
-            let block = LeafBlock::new_from_token(token)?;
+            let mut block = LeafBlock::new_from_token(token)?;
+            if self.block_title.is_some() {
+                block.title = self.block_title.as_ref().unwrap().clone();
+                self.block_title = None;
+            }
             self.push_block_to_stack(Block::LeafBlock(block))?;
             // note that we're to just add
             self.force_new_block = false;
@@ -1107,8 +1573,43 @@ impl Parser {
         }
     }
 
+    /// Open blocks styled as another kind of delimited block (e.g., "[source]" or "[sidebar]")
+    /// masquerade as that block; "abstract" and "partintro" (among other styles) stay open blocks
+    fn masquerade_open_block(&mut self, token: &mut Token) {
+        let Some(style) = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.block_style())
+        else {
+            return;
+        };
+        let (token_type, verbatim) = match style {
+            "source" | "listing" => (TokenType::SourceBlock, true),
+            "literal" => (TokenType::LiteralBlock, true),
+            "pass" | "stem" | "latexmath" | "asciimath" => (TokenType::PassthroughBlock, true),
+            "comment" => (TokenType::CommentBlock, true),
+            "verse" => (TokenType::QuoteVerseBlock, true),
+            "quote" => (TokenType::QuoteVerseBlock, false),
+            "sidebar" => (TokenType::SidebarBlock, false),
+            "example" => (TokenType::ExampleBlock, false),
+            _ => return,
+        };
+        token.token_type = token_type;
+        self.open_block_masquerade = verbatim;
+    }
+
+    /// Only the "--" closes an open block masquerading as a verbatim block, so its native
+    /// delimiter (e.g., "----" in a "[source]" open block) is just text
+    fn demote_masqueraded_delimiter(&self, token: &mut Token) {
+        if self.open_block_masquerade
+            && self.open_parse_after_as_text_type == Some(token.token_type())
+        {
+            token.token_type = TokenType::Text;
+        }
+    }
+
     fn parse_delimited_parent_block(&mut self, token: Token) -> Result<(), ParserError> {
-        let delimiter_line = token.first_location().line;
+        let mut delimiter_line = token.first_location().line;
         let mut block = ParentBlock::new_from_token(token)?;
         // clear the dangling newline
         self.dangling_newline = None;
@@ -1118,6 +1619,22 @@ impl Parser {
             self.block_title = None;
         }
 
+        // an example block styled as an admonition, e.g., "[NOTE]" followed by "====", becomes
+        // the (delimited) admonition itself
+        if self.close_parent_after_push
+            && let Some(Block::ParentBlock(admonition)) = self.block_stack.last()
+            && admonition.accepts_delimiter(&block)
+        {
+            let Some(Block::ParentBlock(mut admonition)) = self.block_stack.pop() else {
+                return Err(ParserError::ParentBlock(delimiter_line));
+            };
+            admonition.delimit(block);
+            delimiter_line = admonition.opening_line()?;
+            self.close_parent_after_push = false;
+            self.open_delimited_block_lines.push(delimiter_line);
+            return self.push_block_to_stack(Block::ParentBlock(admonition));
+        }
+
         // check for any prior parents in reverse
         if let Some(parent_block_idx) = self
             .block_stack
@@ -1128,7 +1645,7 @@ impl Parser {
             let Block::ParentBlock(mut matched) = matched_block else {
                 return Err(ParserError::ParentBlock(delimiter_line));
             };
-            if matched == block {
+            if matched == block || matched.is_closed_by(&block) {
                 // close any dangling inlines BEFORE opening the delimited block lines
                 self.add_inlines_to_block_stack()?;
                 // remove the open delimiter line from the count and confirm we're nested properly
@@ -1198,6 +1715,7 @@ impl Parser {
         // we only want to push on continue if we're not in an open delimited block (which will
         // close itself, emptying the open_delimited_block_lines)
         if self.in_block_continuation && self.open_delimited_block_lines.is_empty() {
+            self.caption_block(&mut block);
             let Some(last_block) = self.block_stack.last_mut() else {
                 return Err(ParserError::BlockContinuation);
             };
@@ -1208,24 +1726,61 @@ impl Parser {
                 block.add_metadata(self.metadata.as_ref().unwrap().clone())?;
                 self.metadata = None;
             }
+            self.stem_block(&mut block);
+            self.caption_block(&mut block);
             self.block_stack.push(block)
         }
         Ok(())
     }
 
+    /// Turns passthrough blocks styled "stem", "latexmath", or "asciimath" into STEM blocks,
+    /// noting the notation in the block's metadata
+    fn stem_block(&self, block: &mut Block) {
+        let Block::LeafBlock(leaf) = block else {
+            return;
+        };
+        if leaf.name != LeafBlockName::Pass {
+            return;
+        }
+        let Some(metadata) = leaf.metadata.as_mut() else {
+            return;
+        };
+        let notation = match metadata.attributes.get("positional_1").map(|s| s.as_str()) {
+            Some("stem") => self.stem_notation(),
+            Some("latexmath") => Notation::LatexMath,
+            Some("asciimath") => Notation::AsciiMath,
+            _ => return,
+        };
+        metadata
+            .attributes
+            .insert("notation".to_string(), notation.name().to_string());
+        leaf.name = LeafBlockName::Stem;
+    }
+
+    /// Numbers the caption of titled figures, tables, examples, and listings
+    fn caption_block(&mut self, block: &mut Block) {
+        self.caption_numbering.caption_block(
+            block,
+            &self.document_attributes,
+            &self.unset_attributes,
+        );
+    }
+
     fn add_text_to_last_inline(&mut self, token: Token) {
         let inline_literal = Inline::InlineLiteral(InlineLiteral::new_text_from_token(&token));
         if let Some(last_inline) = self.inline_stack.back_mut() {
             match last_inline {
                 Inline::InlineSpan(span) => {
-                    if self.in_inline_span {
+                    if self.in_inline_span && span.open {
                         span.add_inline(inline_literal);
                     } else {
                         self.inline_stack.push_back(inline_literal)
                     }
                 }
                 Inline::InlineLiteral(prior_literal) => {
-                    if matches!(prior_literal.name, InlineLiteralName::Charref) {
+                    if matches!(prior_literal.name, InlineLiteralName::Charref)
+                        || !prior_literal.subs().is_empty()
+                    {
                         self.inline_stack.push_back(inline_literal)
                     } else {
                         prior_literal.add_text_from_token(&token)
@@ -1249,26 +1804,33 @@ impl Parser {
     }
 
     fn pass_text_to_last_inline(&mut self, token: Token) {
-        let inline_literal = Inline::InlineLiteral(InlineLiteral::new_text_from_token_pass(&token));
+        let subs = self.passed_text_subs();
+        let inline_literal = Inline::InlineLiteral(
+            InlineLiteral::new_text_from_token_pass(&token).with_subs(subs.clone()),
+        );
         if let Some(last_inline) = self.inline_stack.back_mut() {
             match last_inline {
                 Inline::InlineSpan(span) => {
-                    if self.in_inline_span {
+                    if self.in_inline_span && span.open {
                         span.add_inline(inline_literal);
                     } else {
                         self.inline_stack.push_back(inline_literal)
                     }
                 }
                 Inline::InlineLiteral(prior_literal) => {
-                    if matches!(prior_literal.name, InlineLiteralName::Charref) {
+                    if matches!(prior_literal.name, InlineLiteralName::Charref)
+                        || prior_literal.subs() != subs
+                    {
                         self.inline_stack.push_back(inline_literal)
                     } else {
                         prior_literal.pass_text_from_token(&token)
                     }
                 }
                 Inline::InlineRef(inline_ref) => {
-                    if !self.close_parent_after_push {
+                    if !self.close_parent_after_push && subs.is_empty() {
                         inline_ref.pass_text_from_token(token)
+                    } else if !self.close_parent_after_push {
+                        inline_ref.inlines.push(inline_literal)
                     } else {
                         self.inline_stack.push_back(inline_literal)
                     }
@@ -1300,10 +1862,15 @@ impl Parser {
                     Inline::InlineLiteral(literal) => {
                         literal.prepend_to_value(open_span_literal, open_span.locations());
                     }
-                    _ => todo!(),
+                    // e.g., an email address or another span right after the opening mark
+                    _ => children.push_front(Inline::InlineLiteral(InlineLiteral::new(
+                        InlineLiteralName::Text,
+                        open_span_literal,
+                        open_span.locations(),
+                    ))),
                 }
                 // put any appended inlines into the stack at the relevant position
-                while children.len() > 0 {
+                while !children.is_empty() {
                     if let Some(child) = children.pop_back() {
                         self.inline_stack.insert(open_span_idx, child);
                     }
@@ -1312,10 +1879,12 @@ impl Parser {
                 // consolidate any resultant or remaining adjacent literals (this should be extracted to a function)
                 let mut temp_stack: Vec<Inline> = vec![];
                 while let Some(mut inline) = self.inline_stack.pop_front() {
-                    if temp_stack.len() == 0 {
+                    if temp_stack.is_empty() {
                         temp_stack.push(inline);
-                    } else if inline.is_literal() {
-                        if let Some(Inline::InlineLiteral(last_in_stack)) = temp_stack.last_mut() {
+                    } else if let Inline::InlineLiteral(literal) = &inline {
+                        if let Some(Inline::InlineLiteral(last_in_stack)) = temp_stack.last_mut()
+                            && last_in_stack.subs() == literal.subs()
+                        {
                             last_in_stack.combine_literals(inline.extract_literal());
                         } else {
                             temp_stack.push(inline);
@@ -1346,6 +1915,8 @@ impl Parser {
     }
 
     fn add_inlines_to_block_stack(&mut self) -> Result<(), ParserError> {
+        // an unterminated "pass:q[" ends with the inlines it was opened among
+        self.open_passthrough_macro = None;
         // guard
         if self.inline_stack.is_empty() {
             return Ok(());
@@ -1363,14 +1934,16 @@ impl Parser {
             return Ok(());
         }
 
-        if let Some(last_block) = self.block_stack.last_mut() {
-            if last_block.takes_inlines() && !self.in_block_line && !self.force_new_block {
-                while !self.inline_stack.is_empty() {
-                    let inline = self.inline_stack.pop_front().unwrap();
-                    last_block.push_inline(inline)?;
-                }
-                return Ok(());
+        if let Some(last_block) = self.block_stack.last_mut()
+            && last_block.takes_inlines()
+            && !self.in_block_line
+            && !self.force_new_block
+        {
+            while !self.inline_stack.is_empty() {
+                let inline = self.inline_stack.pop_front().unwrap();
+                last_block.push_inline(inline)?;
             }
+            return Ok(());
         }
         // create a new para from the locations of the first span (subsequent locations are
         // consolidated later)
@@ -1455,11 +2028,11 @@ impl Parser {
         asg: &mut Asg,
         mut block: Block,
     ) -> Result<(), ParserError> {
-        if let Some(last_block) = self.block_stack.last_mut() {
-            if last_block.takes_block_of_type(&block) {
-                last_block.push_block(block)?;
-                return Ok(());
-            }
+        if let Some(last_block) = self.block_stack.last_mut()
+            && last_block.takes_block_of_type(&block)
+        {
+            last_block.push_block(block)?;
+            return Ok(());
         }
         if self.metadata.is_some() {
             block.add_metadata(self.metadata.as_ref().unwrap().clone())?;
@@ -1471,11 +2044,11 @@ impl Parser {
 
     fn add_last_to_block_stack_or_graph(&mut self, asg: &mut Asg) -> Result<(), ParserError> {
         if let Some(last_block) = self.block_stack.pop() {
-            if let Some(prior_block) = self.block_stack.last_mut() {
-                if prior_block.takes_block_of_type(&last_block) {
-                    prior_block.push_block(last_block)?;
-                    return Ok(());
-                }
+            if let Some(prior_block) = self.block_stack.last_mut()
+                && prior_block.takes_block_of_type(&last_block)
+            {
+                prior_block.push_block(last_block)?;
+                return Ok(());
             }
             asg.push_block(last_block)?;
             Ok(())
@@ -1514,6 +2087,17 @@ impl Parser {
         }
     }
 
+    /// Checks whether the document referenced by an xref (e.g., "other.adoc" or "other") was
+    /// included in the parse
+    fn is_included(&self, document: &str) -> bool {
+        let mut path = self.origin_directory.join(document);
+        if path.extension().is_none() {
+            path.set_extension("adoc");
+        }
+        path.canonicalize()
+            .is_ok_and(|path| self.included_files.contains(&path))
+    }
+
     fn resolve_target(&self, token_line: usize, target: &str) -> Result<PathBuf, ParserError> {
         if !self.file_stack.is_empty() {
             let mut resolved_target = self.origin_directory.clone();
@@ -1536,6 +2120,37 @@ impl Parser {
     }
 }
 
+/// The substitutions given to a "pass:[]" macro, e.g., "pass:q,a[]"; since text isn't otherwise
+/// escaped, only specialchars, quotes, and attributes change the output
+#[derive(Debug, Default, Clone, Copy)]
+struct PassthroughSubs {
+    specialchars: bool,
+    quotes: bool,
+    attributes: bool,
+}
+
+impl PassthroughSubs {
+    fn from_macro(lexeme: &str) -> Self {
+        let mut subs = PassthroughSubs::default();
+        let sub_list = lexeme.trim_start_matches("pass:").trim_end_matches('[');
+        for sub in sub_list.split(',') {
+            match sub {
+                "c" | "specialchars" => subs.specialchars = true,
+                "q" | "quotes" => subs.quotes = true,
+                "a" | "attributes" => subs.attributes = true,
+                "v" | "verbatim" => subs.specialchars = true,
+                "n" | "normal" => {
+                    subs.specialchars = true;
+                    subs.quotes = true;
+                    subs.attributes = true;
+                }
+                _ => {}
+            }
+        }
+        subs
+    }
+}
+
 fn open_file<P>(filename: P) -> String
 where
     P: AsRef<Path> + Into<PathBuf> + Debug,
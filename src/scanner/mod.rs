@@ -2,10 +2,80 @@
 
 pub mod tokens;
 use core::str;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use tokens::{Token, TokenType};
 
 use crate::errors::ScannerError;
 
+/// e.g., "[[some_id]]" or "[[some_id,Reference text]]"; IDs start with a letter, "_", or ":"
+static RE_INLINE_ANCHOR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[\[[\p{Alpha}_:][\w:.-]*(?:, *[^\]\n]+)?\]\]").unwrap());
+/// e.g., "[[[some_ref]]]" or "[[[some_ref,Label]]]", with the same ID rules as inline anchors
+static RE_BIBLIOGRAPHY_ANCHOR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[\[\[[\p{Alpha}_:][\w:.-]*(?:, *[^\]\n]+)?\]\]\]").unwrap());
+/// The unordered list marker a bibliography anchor must directly follow, e.g., "* " or "- "
+static RE_BIBLIOGRAPHY_ITEM_MARKER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[ \t]*(?:\*+|-)[ \t]+$").unwrap());
+/// e.g., "anchor:some_id[Reference text]"
+static RE_ANCHOR_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^anchor:[\w:.-]+\[[^\]\n]*\]").unwrap());
+static RE_XREF_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^xref:[^\s\[\]]+\[[^\]\n]*\]").unwrap());
+static RE_MAILTO_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^mailto:[^\s\[\]]+@[^\s\[\]]+\[[^\]\n]*\]").unwrap());
+static RE_UI_MACRO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:kbd:\[(?:\\\]|[^\]\n])+\]|btn:\[[^\]\n]+\]|menu:[^\s\[\]]+\[[^\]\n]*\])")
+        .unwrap()
+});
+static RE_STEM_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:stem|latexmath|asciimath):\[(?:\\\]|[^\]\n])+\]").unwrap());
+/// e.g., "((flow term))", "(((concealed, secondary)))", "indexterm:[concealed]", or
+/// "indexterm2:[flow term]"
+static RE_INDEX_TERM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\(\(\([^\n]+?\)\)\)|\(\([^\n]+?\)\)|indexterm2?:\[[^\]\n]+\])").unwrap()
+});
+/// e.g., "footnote:disclaimer[", which is closed like any other inline macro
+static RE_FOOTNOTE_MACRO: Lazy<Regex> = Lazy::new(|| Regex::new(r"^footnote:[\w-]+\[").unwrap());
+/// Block audio and video macros, e.g., "audio::file.mp3[]" or "video::id[youtube]"
+static RE_BLOCK_MEDIA_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:audio|video)::[^\s\[]+\[[^\]\n]*\][ \t]*(?:\n|$)").unwrap());
+/// e.g., "pass:[", or "pass:q,a[" with an explicit substitution list
+static RE_PASS_MACRO: Lazy<Regex> = Lazy::new(|| Regex::new(r"^pass:[a-z,]*\[").unwrap());
+/// e.g., "+++raw+++", "++unconstrained++", or "$$raw$$"; the constrained "+text+" form is
+/// checked separately, since it needs word boundaries
+static RE_INLINE_PASSTHROUGH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:\+\+\+[^\n]+?\+\+\+|\+\+[^\n]+?\+\+|\$\$[^\n]+?\$\$)").unwrap());
+static RE_CONSTRAINED_PASSTHROUGH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\+[^\s+](?:[^\n+]*[^\s+])?\+").unwrap());
+/// Named, decimal, or hexadecimal character references, e.g., "&mdash;", "&#8212;", or "&#x2014;"
+static RE_CHARREF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^&(?:[A-Za-z][A-Za-z0-9]*|#[0-9]+|#[xX][0-9A-Fa-f]+);").unwrap());
+/// Markup that can be escaped with a preceding backslash, e.g., "\{attr}", "\<<not-xref>>", or
+/// "\https://example.com"; escaped inline formatting is handled separately
+static RE_ESCAPABLE_MARKUP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:\{\w[\w-]*\}|<<[^\n]*?>>|\[\[[^\n]*?\]\]|\(\(\(?[^\n]+?\)\)\)?|&#?\w+;|(?:https?|ftp|irc)://[^\s\[]+(?:\[[^\]\n]*\])?|[a-z][a-z0-9]*:[^\s\[]*\[[^\]\n]*\])",
+    )
+    .unwrap()
+});
+/// Indented lines that nonetheless start list items (e.g., "  * foo" or "  1. foo") or
+/// description list terms (e.g., "  Term:: foo") rather than literal paragraphs
+static RE_INDENTED_LIST_ITEM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(?:[*.-]+|\d+\.|[a-zA-Z]\.|<(?:\d+|\.)>)\s|[^\s].*?(?:::|;;)(?:\s|$))")
+        .unwrap()
+});
+static RE_CODE_CALLOUT_LIST_ITEM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^<(?:\d+|\.)> ").unwrap());
+/// Code callouts, e.g., "<1>", "<.>", or "<!--1-->", must only be followed by other callouts on
+/// the same line
+static RE_CODE_CALLOUT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(<(?:\d+|\.)>|<!--(?:\d+|\.)-->)(?:[ \t]*(?:<(?:\d+|\.)>|<!--(?:\d+|\.)-->))*[ \t]*(?:\n|$)")
+        .unwrap()
+});
+static RE_EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[\w.%+-]+@[\w-]+(?:\.[\w-]+)*\.[A-Za-z]{2,}").unwrap());
+
 #[derive(Debug)]
 /// Scans an asciidoc `&str` into [`Token`]s to be consumed by the Parser.
 pub struct Scanner<'a> {
@@ -67,6 +137,11 @@ impl<'a> Scanner<'a> {
         let c = self.source.as_bytes()[self.current] as char;
         self.current += 1; // this instead of the "advance" function in "Crafting Interpreters"
 
+        // email addresses can start with nearly any character, so check for them first
+        if self.starts_email(self.start) {
+            return self.add_email();
+        }
+
         match c {
             '\n' => self.add_token(TokenType::NewLineChar, false, 1),
             '\'' => {
@@ -93,17 +168,15 @@ impl<'a> Scanner<'a> {
                 } else if self.starts_repeated_char_line(c, 3) {
                     self.current += 2;
                     self.add_token(TokenType::PageBreak, false, 0)
+                } else if self.starts_new_line() && self.starts_code_callout_list_item() {
+                    self.add_token(TokenType::CodeCalloutListItem, true, 0)
+                } else if self.starts_code_callout() {
+                    self.add_token(TokenType::CodeCallout, true, 0)
                 } else if ['-', '='].contains(&self.peek()) {
                     self.current += 1;
                     self.add_token(TokenType::CharRef, false, 0)
                 } else if self.peek() == '<' {
                     self.add_cross_reference()
-                } else if self.starts_new_line() && self.starts_code_callout_list_item() {
-                    self.current += 1; // consume the space char
-                    self.add_token(TokenType::CodeCalloutListItem, true, 0)
-                } else if self.starts_code_callout() {
-                    self.add_token(TokenType::CodeCallout, true, 0)
-                // else if ...
                 } else {
                     self.add_text_until_next_markup()
                 }
@@ -180,12 +253,11 @@ impl<'a> Scanner<'a> {
                                 self.add_token(TokenType::BlockContinuation, false, 0)
                             } else if self.peek_back() == ' ' && self.peek() == '\n' {
                                 self.add_token(TokenType::LineContinuation, false, 0)
+                            } else if let Some(end) = self.inline_passthrough_end() {
+                                self.current = end;
+                                self.add_token(TokenType::InlinePassthrough, true, 0)
                             } else {
-                                self.handle_inline_formatting(
-                                    c,
-                                    TokenType::Literal,
-                                    TokenType::UnconstrainedLiteral,
-                                )
+                                self.add_text_until_next_markup()
                             }
                         }
                         '_' => self.handle_inline_formatting(
@@ -216,8 +288,12 @@ impl<'a> Scanner<'a> {
 
             '[' => {
                 // block anchor, role, quote, verse, source, etc. TK add for generic options
-                if self.starts_new_line() && self.peek() == '[' {
+                if self.starts_new_line() && self.peek() == '[' && self.starts_block_anchor() {
                     self.add_block_anchor()
+                } else if self.peeks_ahead(2) == "[[" && self.starts_bibliography_anchor() {
+                    self.add_bibliography_anchor()
+                } else if self.peek() == '[' && self.starts_inline_anchor() {
+                    self.add_inline_anchor()
                 } else if self.starts_attribution_line() {
                     match self.source.as_bytes()[self.start + 1] as char {
                         'N' => self.add_token(TokenType::NotePara, true, 0),
@@ -227,7 +303,7 @@ impl<'a> Scanner<'a> {
                         'W' => self.add_token(TokenType::WarningPara, true, 0),
                         _ => self.add_token(TokenType::ElementAttributes, true, 0),
                     }
-                } else if self.peek() == '.' {
+                } else if ['.', '#'].contains(&self.peek()) && self.starts_inline_style() {
                     self.add_inline_style()
                 } else {
                     self.add_text_until_next_markup()
@@ -277,7 +353,9 @@ impl<'a> Scanner<'a> {
                 }
             }
             '(' => {
-                if self.starts_text_symbol_replace_parens() {
+                if self.starts_index_term(self.start) {
+                    self.add_index_term()
+                } else if self.starts_text_symbol_replace_parens() {
                     self.handle_text_symbol_replacement_parens()
                 } else {
                     self.add_text_until_next_markup()
@@ -288,13 +366,17 @@ impl<'a> Scanner<'a> {
                 if self.peeks_ahead(9) == "ootnote:[" {
                     self.current += 9;
                     self.add_token(TokenType::FootnoteMacro, false, 0)
+                } else if let Some(footnote) = RE_FOOTNOTE_MACRO.find(&self.source[self.start..]) {
+                    // named footnotes, e.g., "footnote:disclaimer[Some text]"
+                    self.current = self.start + footnote.end();
+                    self.add_token(TokenType::FootnoteMacro, false, 0)
                 } else {
                     self.add_text_until_next_markup()
                 }
             }
             'p' => {
-                if self.peeks_ahead(5) == "ass:[" {
-                    self.current += 5;
+                if let Some(pass_macro) = RE_PASS_MACRO.find(&self.source[self.start..]) {
+                    self.current = self.start + pass_macro.end();
                     self.add_token(TokenType::PassthroughInlineMacro, false, 0)
                 } else {
                     self.add_text_until_next_markup()
@@ -310,6 +392,8 @@ impl<'a> Scanner<'a> {
             'i' => {
                 if self.starts_new_line() && self.peeks_ahead(8) == "nclude::" {
                     self.add_include()
+                } else if self.starts_index_term(self.start) {
+                    self.add_index_term()
                 } else if self.starts_new_line() && self.peeks_ahead(6) == "mage::" {
                     self.add_block_image()
                 // double colons after just parse as regular text per asciidoctor implementation
@@ -376,17 +460,30 @@ impl<'a> Scanner<'a> {
                     self.add_text_until_next_markup()
                 }
             }
+            '$' => {
+                if let Some(end) = self.inline_passthrough_end() {
+                    self.current = end;
+                    self.add_token(TokenType::InlinePassthrough, true, 0)
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            '\\' => {
+                if let Some(end) = self.escaped_markup_end() {
+                    self.current = end;
+                    self.add_escaped_text()
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
             // Assume that these are macro closes; the parser can always reject it
             ']' => self.add_token(TokenType::InlineMacroClose, true, 0),
             // & chars followed by some number of characters ending with ";" should be considered
             // CharRef tokens... why we need to collect these I'm not entirely sure, but it's in
             // the spec
             '&' => {
-                if self.starts_charref() {
-                    while self.peek().is_alphanumeric() && !self.is_at_end() {
-                        self.current += 1
-                    }
-                    self.current += 1; // add the ";" at the end
+                if let Some(charref) = RE_CHARREF.find(&self.source[self.start..]) {
+                    self.current = self.start + charref.end();
                     self.add_token(TokenType::CharRef, true, 0)
                 } else {
                     self.add_text_until_next_markup()
@@ -428,9 +525,62 @@ impl<'a> Scanner<'a> {
                     self.add_text_until_next_markup()
                 }
             }
-            ' ' | '%' => {
+            ' ' | '\t' | '%' => {
                 if self.starts_end_tag_line() {
                     self.add_tag()
+                } else if c != '%' && self.starts_literal_paragraph() {
+                    while [' ', '\t'].contains(&self.peek()) {
+                        self.current += 1
+                    }
+                    self.add_token(TokenType::LiteralParagraph, true, 0)
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            'a' => {
+                if self.starts_block_media_macro() {
+                    self.add_block_media_macro(TokenType::BlockAudioMacro)
+                } else if self.starts_anchor_macro(self.start) {
+                    self.add_inline_anchor_macro()
+                } else if self.starts_stem_macro(self.start) {
+                    self.add_stem_macro()
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            'v' => {
+                if self.starts_block_media_macro() {
+                    self.add_block_media_macro(TokenType::BlockVideoMacro)
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            'm' => {
+                if self.starts_mailto_macro(self.start) {
+                    self.add_mailto_macro()
+                } else if self.starts_ui_macro(self.start) {
+                    self.add_ui_macro()
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            'k' | 'b' => {
+                if self.starts_ui_macro(self.start) {
+                    self.add_ui_macro()
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            's' | 'l' => {
+                if self.starts_stem_macro(self.start) {
+                    self.add_stem_macro()
+                } else {
+                    self.add_text_until_next_markup()
+                }
+            }
+            'x' => {
+                if self.starts_xref_macro(self.start) {
+                    self.add_cross_reference_macro()
                 } else {
                     self.add_text_until_next_markup()
                 }
@@ -514,6 +664,15 @@ impl<'a> Scanner<'a> {
         self.add_token(TokenType::BlockImageMacro, true, 0)
     }
 
+    /// Adds the block audio or video macro, consuming the target as well as any attributes
+    fn add_block_media_macro(&mut self, token_type: TokenType) -> Result<Token, ScannerError> {
+        while self.peek() != ']' {
+            self.current += 1
+        }
+        self.current += 1; // consume the ']' char
+        self.add_token(token_type, true, 0)
+    }
+
     /// Adds the block image, consuming the target as well as any attributes
     fn add_inline_image(&mut self) -> Result<Token, ScannerError> {
         while self.peek() != ']' {
@@ -585,7 +744,7 @@ impl<'a> Scanner<'a> {
         self.add_token(TokenType::InlineStyle, true, 0)
     }
 
-    // adds block anchors, e.g., "\n[[some_block_id]]\n"
+    // adds cross references, e.g., "<<some_id>>" or "<<other.adoc#some_id,Some text>>"
     fn add_cross_reference(&mut self) -> Result<Token, ScannerError> {
         while self.peeks_ahead(2) != ">>" && !self.is_at_end() {
             self.current += 1
@@ -596,29 +755,120 @@ impl<'a> Scanner<'a> {
 
     // adds block anchors, e.g., "\n[[some_block_id]]\n"
     fn add_block_anchor(&mut self) -> Result<Token, ScannerError> {
-        while self.peeks_ahead(3) != "]]\n" && !self.is_at_end() {
+        while self.peeks_ahead(2) != "]]" && !self.is_at_end() {
             self.current += 1
         }
         self.current += 2; // consume the ']]' chars, NOT newline
         self.add_token(TokenType::BlockAnchor, true, 0)
     }
 
+    // adds inline anchors, e.g., "[[some_id]]Some text" or "[[some_id,Reference text]]"
+    fn add_inline_anchor(&mut self) -> Result<Token, ScannerError> {
+        if let Some(anchor) = RE_INLINE_ANCHOR.find(&self.source[self.start..]) {
+            self.current = self.start + anchor.end();
+        }
+        self.add_token(TokenType::InlineAnchor, true, 0)
+    }
+
+    // adds bibliography anchors, e.g., "[[[some_ref]]]" or "[[[some_ref,Label]]]"
+    fn add_bibliography_anchor(&mut self) -> Result<Token, ScannerError> {
+        if let Some(anchor) = RE_BIBLIOGRAPHY_ANCHOR.find(&self.source[self.start..]) {
+            self.current = self.start + anchor.end();
+        }
+        self.add_token(TokenType::InlineAnchor, true, 0)
+    }
+
+    // adds email addresses, e.g., "cats@dogs.foo"
+    fn add_email(&mut self) -> Result<Token, ScannerError> {
+        if let Some(email) = RE_EMAIL.find(&self.source[self.start..]) {
+            self.current = self.start + email.end();
+        }
+        self.add_token(TokenType::Email, true, 0)
+    }
+
+    // adds mailto macros, e.g., "mailto:cats@dogs.foo[Text, Subject, Body]"
+    fn add_mailto_macro(&mut self) -> Result<Token, ScannerError> {
+        while self.peek() != ']' {
+            self.current += 1
+        }
+        self.current += 1; // consume the ']' char
+        self.add_token(TokenType::Email, true, 0)
+    }
+
+    // adds keyboard, button, and menu macros, e.g., "kbd:[Ctrl+T]"
+    fn add_ui_macro(&mut self) -> Result<Token, ScannerError> {
+        if let Some(ui_macro) = RE_UI_MACRO.find(&self.source[self.start..]) {
+            self.current = self.start + ui_macro.end();
+        }
+        let token_type = match self.source.as_bytes()[self.start] {
+            b'k' => TokenType::KeyboardMacro,
+            b'b' => TokenType::ButtonMacro,
+            _ => TokenType::MenuMacro,
+        };
+        self.add_token(token_type, true, 0)
+    }
+
+    // adds STEM macros, e.g., "stem:[sqrt(4) = 2]" or "latexmath:[\sqrt{4} = 2]"
+    fn add_stem_macro(&mut self) -> Result<Token, ScannerError> {
+        if let Some(stem_macro) = RE_STEM_MACRO.find(&self.source[self.start..]) {
+            self.current = self.start + stem_macro.end();
+        }
+        self.add_token(TokenType::StemMacro, true, 0)
+    }
+
+    // adds index terms, e.g., "((flow term))" or "indexterm:[primary, secondary]"
+    fn add_index_term(&mut self) -> Result<Token, ScannerError> {
+        if let Some(index_term) = RE_INDEX_TERM.find(&self.source[self.start..]) {
+            self.current = self.start + index_term.end();
+        }
+        self.add_token(TokenType::IndexTerm, true, 0)
+    }
+
+    // adds cross reference macros, e.g., "xref:other.adoc#some_id[Some text]"
+    fn add_cross_reference_macro(&mut self) -> Result<Token, ScannerError> {
+        while self.peek() != ']' {
+            self.current += 1
+        }
+        self.current += 1; // consume the ']' char
+        self.add_token(TokenType::CrossReference, true, 0)
+    }
+
+    // adds anchor macros, e.g., "anchor:some_id[Reference text]"
+    fn add_inline_anchor_macro(&mut self) -> Result<Token, ScannerError> {
+        while self.peek() != ']' {
+            self.current += 1
+        }
+        self.current += 1; // consume the ']' char
+        self.add_token(TokenType::InlineAnchor, true, 0)
+    }
+
     fn add_text_until_next_markup(&mut self) -> Result<Token, ScannerError> {
         // "inline" chars that could be markup; the newline condition prevents
         // capturing significant block markup chars
-        // Chars: newline, bold, italic, code, super, subscript, footnote, pass, link, end inline macro, definition list marker, highlighted, inline admonition initial chars, inline images
+        // Chars: newline, bold, italic, code, super, subscript, footnote, pass, link, end inline macro, definition list marker, highlighted, inline admonition initial chars, inline images, legacy passthroughs, backslash escapes
         while ![
             '\n', '*', '_', '`', '^', '~', 'f', 'p', 'h', ']', '[', ':', '#', 'N', 'T', 'I', 'C',
-            'W', '&', '{', '+', 'i', '<', '\'', '\"', '-', 't', 'e',
+            'W', '&', '{', '+', 'i', '<', '\'', '\"', '-', 't', 'e', '$', '\\',
         ]
         .contains(&self.peek())
             && !self.is_at_end()
         {
+            if self.starts_word_markup(self.current) {
+                break;
+            }
             self.current += 1;
         }
         self.add_token(TokenType::Text, true, 0)
     }
 
+    /// Adds escaped markup as text, with the backslash removed from the literal (but not the
+    /// lexeme, so that the backslash is kept in source blocks and the like)
+    fn add_escaped_text(&mut self) -> Result<Token, ScannerError> {
+        let mut token = self.add_token(TokenType::Text, false, 0)?;
+        token.literal = Some(token.lexeme[1..].to_string());
+        Ok(token)
+    }
+
     fn add_tag(&mut self) -> Result<Token, ScannerError> {
         let tag_type: TokenType;
         if self.expecting_tag_end {
@@ -655,6 +905,197 @@ impl<'a> Scanner<'a> {
         result
     }
 
+    /// Checks for an indented line starting a paragraph (i.e., at the start of the document, or
+    /// after a blank line, block attribute list, or block title), which makes the paragraph a
+    /// literal block; indented list items are still list items
+    fn starts_literal_paragraph(&self) -> bool {
+        if !self.starts_new_line() {
+            return false;
+        }
+        if self.start > 0 {
+            let prior_line = self.source[..self.start - 1]
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default();
+            let starts_paragraph = prior_line.is_empty()
+                || (prior_line.starts_with('[') && prior_line.ends_with(']'))
+                || (prior_line.starts_with('.')
+                    && prior_line[1..].starts_with(|c: char| c.is_alphanumeric()));
+            if !starts_paragraph {
+                return false;
+            }
+        }
+        let line = self.source[self.start..].lines().next().unwrap_or_default();
+        let text = line.trim_start();
+        !text.is_empty() && !RE_INDENTED_LIST_ITEM.is_match(text)
+    }
+
+    /// Checks for "[[some_block_id]]" lines, where the anchor is the only thing on the line
+    fn starts_block_anchor(&self) -> bool {
+        let line = self.peek_line();
+        line.find("]]") == Some(line.trim_end().len().saturating_sub(2))
+    }
+
+    /// Checks for "[[some_id]]" or "[[some_id,Reference text]]" with a valid ID, so that, e.g.,
+    /// "[[1, 2], [3]]" stays text
+    fn starts_inline_anchor(&self) -> bool {
+        RE_INLINE_ANCHOR.is_match(&self.source[self.start..])
+    }
+
+    /// Checks for "[[[some_ref]]]" or "[[[some_ref,Label]]]" with a valid ID at the start of an
+    /// unordered list item, e.g., "* [[[pp]]] Andy Hunt & Dave Thomas"
+    fn starts_bibliography_anchor(&self) -> bool {
+        let line_start = self.source[..self.start]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        RE_BIBLIOGRAPHY_ITEM_MARKER.is_match(&self.source[line_start..self.start])
+            && RE_BIBLIOGRAPHY_ANCHOR.is_match(&self.source[self.start..])
+    }
+
+    /// Checks for "anchor:some_id[]" or "anchor:some_id[Reference text]" starting at a word
+    /// boundary at the given position
+    fn starts_anchor_macro(&self, position: usize) -> bool {
+        if position > 0 && (self.source.as_bytes()[position - 1] as char).is_alphanumeric() {
+            return false;
+        }
+        RE_ANCHOR_MACRO.is_match(&self.source[position..])
+    }
+
+    /// Checks for inline markup that starts a word rather than being signaled by a given
+    /// character, e.g., "anchor:", "xref:", "mailto:", and UI macros, or email addresses
+    fn starts_word_markup(&self, position: usize) -> bool {
+        match self.source.as_bytes()[position] as char {
+            'a' if self.starts_anchor_macro(position) => true,
+            'x' if self.starts_xref_macro(position) => true,
+            'm' if self.starts_mailto_macro(position) => true,
+            'k' | 'b' | 'm' if self.starts_ui_macro(position) => true,
+            's' | 'l' | 'a' if self.starts_stem_macro(position) => true,
+            '(' | 'i' if self.starts_index_term(position) => true,
+            _ => self.starts_email(position),
+        }
+    }
+
+    /// Checks for the keyboard, button, and menu UI macros, e.g., "kbd:[Ctrl+T]", "btn:[Save]",
+    /// or "menu:File[Save As]", starting at a word boundary at the given position
+    fn starts_ui_macro(&self, position: usize) -> bool {
+        if position > 0 && (self.source.as_bytes()[position - 1] as char).is_alphanumeric() {
+            return false;
+        }
+        RE_UI_MACRO.is_match(&self.source[position..])
+    }
+
+    /// Checks for block audio and video macros, which must be on a line of their own
+    fn starts_block_media_macro(&self) -> bool {
+        self.starts_new_line() && RE_BLOCK_MEDIA_MACRO.is_match(&self.source[self.start..])
+    }
+
+    /// Checks for the "stem:[]", "latexmath:[]", and "asciimath:[]" macros starting at a word
+    /// boundary at the given position
+    fn starts_stem_macro(&self, position: usize) -> bool {
+        if position > 0 && (self.source.as_bytes()[position - 1] as char).is_alphanumeric() {
+            return false;
+        }
+        RE_STEM_MACRO.is_match(&self.source[position..])
+    }
+
+    /// Checks for flow and concealed index terms at the given position, e.g., "((flow term))" or
+    /// "(((concealed)))"; the "indexterm:[]" and "indexterm2:[]" macros must start a word
+    fn starts_index_term(&self, position: usize) -> bool {
+        if self.source.as_bytes()[position] == b'i'
+            && position > 0
+            && (self.source.as_bytes()[position - 1] as char).is_alphanumeric()
+        {
+            return false;
+        }
+        RE_INDEX_TERM.is_match(&self.source[position..])
+    }
+
+    /// Finds the end of the markup escaped by the backslash at the start of the current token,
+    /// e.g., "\*not bold*", "\{attr}", or "\include::foo.adoc[]"; inline formatting is escaped
+    /// through its closing mark if it's on the same line
+    fn escaped_markup_end(&self) -> Option<usize> {
+        let remaining = &self.source[self.current..];
+        if self.starts_new_line() && remaining.starts_with("include::") {
+            return Some(self.current + remaining.find('\n').unwrap_or(remaining.len()));
+        }
+        if let Some(markup) = RE_ESCAPABLE_MARKUP.find(remaining) {
+            return Some(self.current + markup.end());
+        }
+        let c = remaining.chars().next()?;
+        if !['*', '_', '`', '#', '^', '~', '+', '$'].contains(&c) {
+            return None;
+        }
+        let mark_len = remaining
+            .chars()
+            .take_while(|next| *next == c)
+            .count()
+            .min(3);
+        let mark = &remaining[..mark_len];
+        let line = &remaining[mark_len..remaining.find('\n').unwrap_or(remaining.len())];
+        match line.find(mark) {
+            Some(close) if close > 0 => Some(self.current + mark_len + close + mark_len),
+            _ => Some(self.current + mark_len),
+        }
+    }
+
+    /// Finds the end of an inline passthrough starting at the current token, e.g., "+++raw+++",
+    /// "$$raw$$", "++unconstrained++", or a "+constrained+" passthrough bounded by non-word
+    /// characters
+    fn inline_passthrough_end(&self) -> Option<usize> {
+        let remaining = &self.source[self.start..];
+        if let Some(passthrough) = RE_INLINE_PASSTHROUGH.find(remaining) {
+            return Some(self.start + passthrough.end());
+        }
+        if self.start > 0 && (self.source.as_bytes()[self.start - 1] as char).is_alphanumeric() {
+            return None;
+        }
+        let passthrough = RE_CONSTRAINED_PASSTHROUGH.find(remaining)?;
+        match remaining[passthrough.end()..].chars().next() {
+            Some(next) if next.is_alphanumeric() || next == '_' => None,
+            _ => Some(self.start + passthrough.end()),
+        }
+    }
+
+    /// Checks for "mailto:address[]" or "mailto:address[Text, Subject, Body]" starting at a word
+    /// boundary at the given position
+    fn starts_mailto_macro(&self, position: usize) -> bool {
+        if position > 0 && (self.source.as_bytes()[position - 1] as char).is_alphanumeric() {
+            return false;
+        }
+        RE_MAILTO_MACRO.is_match(&self.source[position..])
+    }
+
+    /// Checks for an email address starting at the given position, so long as it isn't part of
+    /// a larger word, address, or URL
+    fn starts_email(&self, position: usize) -> bool {
+        if !(self.source.as_bytes()[position] as char).is_ascii_alphanumeric() {
+            return false;
+        }
+        if position > 0 {
+            let prior = self.source.as_bytes()[position - 1] as char;
+            if prior.is_alphanumeric() || ['.', '%', '+', '-', ':', '/', '@'].contains(&prior) {
+                return false;
+            }
+        }
+        RE_EMAIL.is_match(&self.source[position..])
+    }
+
+    /// Checks for "xref:target[]" or "xref:target[Some text]" starting at a word boundary at the
+    /// given position
+    fn starts_xref_macro(&self, position: usize) -> bool {
+        if position > 0 && (self.source.as_bytes()[position - 1] as char).is_alphanumeric() {
+            return false;
+        }
+        RE_XREF_MACRO.is_match(&self.source[position..])
+    }
+
+    /// Checks for "[.role]" or "[#id]" (or combinations thereof), closed on the same line
+    fn starts_inline_style(&self) -> bool {
+        let line = self.peek_line();
+        line.find(']')
+            .is_some_and(|end| end > 1 && !line[..end].contains(char::is_whitespace))
+    }
+
     fn starts_new_line(&self) -> bool {
         self.start == 0 || self.source.as_bytes()[self.start - 1] == b'\n'
     }
@@ -697,17 +1138,6 @@ impl<'a> Scanner<'a> {
         check
     }
 
-    /// Checks for CharRefs, i.e., &plus; type things
-    fn starts_charref(&mut self) -> bool {
-        let current_placeholder = self.current;
-        while self.peek().is_alphanumeric() && !self.is_at_end() {
-            self.current += 1
-        }
-        let check = self.source.as_bytes()[self.current] as char == ';';
-        self.current = current_placeholder;
-        check
-    }
-
     /// Handles inline formatting, constrained or not
     fn handle_inline_formatting(
         &mut self,
@@ -718,9 +1148,10 @@ impl<'a> Scanner<'a> {
         let inline_markup_chars = ['*', '_', '`', '+', '^', '~', '#'];
         let mut end_of_inline_markers = vec![
             ' ', '\0', '.', ',', ';', ':', '\n', ')', '"', '!', '?', '\'', ']', '…', '“', '”', '‘',
-            '’',
+            '’', '<',
         ];
-        let mut beginning_of_inline_markers = vec![' ', '\n', '\0', ']', '(', '"', '['];
+        // '>' allows for inline HTML in passthroughs, e.g., "pass:q[<u>*strong*</u>]"
+        let mut beginning_of_inline_markers = vec![' ', '\n', '\0', ']', '(', '"', '[', '>'];
         end_of_inline_markers.extend_from_slice(&inline_markup_chars);
         beginning_of_inline_markers.extend_from_slice(&inline_markup_chars);
         // guard clause against dangling markup
@@ -767,28 +1198,26 @@ impl<'a> Scanner<'a> {
         }
     }
 
+    /// Consumes a callout list item marker, e.g., "<1> " or "<.> ", if there is one
     fn starts_code_callout_list_item(&mut self) -> bool {
-        while self.peek() != '>' {
-            if self.peek().is_ascii_digit() {
-                self.current += 1;
-            } else {
-                return false;
+        match RE_CODE_CALLOUT_LIST_ITEM.find(&self.source[self.start..]) {
+            Some(item) => {
+                self.current = self.start + item.end();
+                true
             }
+            None => false,
         }
-        self.current += 1;
-        self.peek() == ' ' // next must be a space
     }
 
+    /// Consumes a code callout, e.g., "<1>", if it's followed only by other callouts on the line
     fn starts_code_callout(&mut self) -> bool {
-        while self.peek() != '>' {
-            if self.peek().is_ascii_digit() {
-                self.current += 1;
-            } else {
-                return false;
+        match RE_CODE_CALLOUT.captures(&self.source[self.start..]) {
+            Some(captures) => {
+                self.current = self.start + captures.get(1).unwrap().end();
+                true
             }
+            None => false,
         }
-        self.current += 1;
-        self.peek() == '\n' // callouts MUST be last chars in line
     }
 
     fn starts_text_symbol_replace_parens(&mut self) -> bool {
@@ -1214,7 +1643,6 @@ mod tests {
     #[case('*', TokenType::Strong)]
     #[case('_', TokenType::Emphasis)]
     #[case('`', TokenType::Monospace)]
-    #[case('+', TokenType::Literal)]
     #[case('^', TokenType::Superscript)]
     #[case('~', TokenType::Subscript)]
     #[case('#', TokenType::Mark)]
@@ -1256,7 +1684,6 @@ mod tests {
     #[case::emphasis(String::from("__"), TokenType::UnconstrainedEmphasis)]
     #[case::monospace(String::from("``"), TokenType::UnconstrainedMonospace)]
     #[case::mark(String::from("##"), TokenType::UnconstrainedMark)]
-    #[case::mark(String::from("++"), TokenType::UnconstrainedLiteral)]
     fn inline_formatting_doubles(#[case] markup_str: String, #[case] expected_token: TokenType) {
         let markup = format!("Somx{}bar{}bar.", markup_str, markup_str);
         let expected_tokens = vec![
@@ -1293,7 +1720,6 @@ mod tests {
     #[rstest]
     #[case('*', TokenType::Strong)]
     #[case('`', TokenType::Monospace)]
-    #[case('+', TokenType::Literal)]
     #[case('^', TokenType::Superscript)]
     #[case('~', TokenType::Subscript)]
     #[case('#', TokenType::Mark)]
@@ -1340,23 +1766,24 @@ mod tests {
     #[case('#')]
     // note that ^ and ~ transformations are handled better by the parser
     fn inline_formatting_by_self_is_text(#[case] markup_char: char) {
-        let markup = format!(" {} ", markup_char);
+        // (a leading space alone would start a literal paragraph)
+        let markup = format!("a {} ", markup_char);
         let expected_tokens = vec![
             Token::new_default(
                 TokenType::Text,
-                " ".to_string(),
-                Some(" ".to_string()),
-                1,
+                "a ".to_string(),
+                Some("a ".to_string()),
                 1,
                 1,
+                2,
             ),
             Token::new_default(
                 TokenType::Text,
                 format!("{} ", markup_char),
                 Some(format!("{} ", markup_char)),
                 1,
-                2,
                 3,
+                4,
             ),
         ];
         scan_and_assert_eq(&markup, expected_tokens);
@@ -1364,7 +1791,9 @@ mod tests {
 
     #[rstest]
     #[case("footnote:[", TokenType::FootnoteMacro)]
+    #[case("footnote:disclaimer[", TokenType::FootnoteMacro)]
     #[case("pass:[", TokenType::PassthroughInlineMacro)]
+    #[case("pass:q,a[", TokenType::PassthroughInlineMacro)]
     fn inline_macros(#[case] markup_check: &str, #[case] expected_token: TokenType) {
         let markup = format!("Somx {}bar]", &markup_check);
         let expected_tokens = vec![
@@ -1934,6 +2363,31 @@ mod tests {
         scan_and_assert_eq(markup, expected_tokens);
     }
 
+    #[rstest]
+    #[case::named("&mdash;")]
+    #[case::decimal("&#8212;")]
+    #[case::hex("&#x2014;")]
+    fn inline_charref_forms(#[case] charref: &str) {
+        let markup = format!("bar{charref}bar");
+        let s = Scanner::new(&markup);
+        let charrefs: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::CharRef)
+            .collect();
+        assert_eq!(
+            charrefs,
+            vec![Token::new_default(
+                TokenType::CharRef,
+                charref.to_string(),
+                Some(charref.to_string()),
+                1,
+                4,
+                3 + charref.len(),
+            )]
+        );
+    }
+
     #[test]
     fn inline_charref_spaces() {
         let markup = "bar &mdash; bar";
@@ -2082,6 +2536,361 @@ mod tests {
         scan_and_assert_eq(&markup, expected_tokens);
     }
 
+    #[rstest]
+    #[case::anchor("[[foo]]")]
+    #[case::anchor_with_reftext("[[foo,Foo Text]]")]
+    #[case::anchor_macro("anchor:foo[Foo Text]")]
+    fn inline_anchor(#[case] anchor: &str) {
+        let markup = format!("Some {anchor}text");
+        let s = Scanner::new(&markup);
+        let anchors: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::InlineAnchor)
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![Token::new_default(
+                TokenType::InlineAnchor,
+                anchor.to_string(),
+                Some(anchor.to_string()),
+                1,
+                6,
+                5 + anchor.len(),
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::email("cats@dogs.foo")]
+    #[case::email_subdomain("cats.meow@mail.dogs.co.uk")]
+    #[case::mailto("mailto:cats@dogs.foo[]")]
+    #[case::mailto_text("mailto:cats@dogs.foo[Cats, Subject, Body text]")]
+    fn email_address(#[case] email: &str) {
+        let markup = format!("Write {email} now");
+        let s = Scanner::new(&markup);
+        let emails: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::Email)
+            .collect();
+        assert_eq!(
+            emails,
+            vec![Token::new_default(
+                TokenType::Email,
+                email.to_string(),
+                Some(email.to_string()),
+                1,
+                7,
+                6 + email.len(),
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::kbd("kbd:[Ctrl+T]", TokenType::KeyboardMacro)]
+    #[case::kbd_escaped_bracket("kbd:[Ctrl+\\]]", TokenType::KeyboardMacro)]
+    #[case::btn("btn:[Save]", TokenType::ButtonMacro)]
+    #[case::menu("menu:File[Save As]", TokenType::MenuMacro)]
+    #[case::menu_no_items("menu:File[]", TokenType::MenuMacro)]
+    fn ui_macros(#[case] ui_macro: &str, #[case] token_type: TokenType) {
+        let markup = format!("Use {ui_macro} now");
+        let s = Scanner::new(&markup);
+        let ui_macros: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == token_type)
+            .collect();
+        assert_eq!(
+            ui_macros,
+            vec![Token::new_default(
+                token_type,
+                ui_macro.to_string(),
+                Some(ui_macro.to_string()),
+                1,
+                5,
+                4 + ui_macro.len(),
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::stem("stem:[sqrt(4) = 2]")]
+    #[case::latexmath("latexmath:[\\frac{1}{2}]")]
+    #[case::asciimath("asciimath:[x_(i)]")]
+    #[case::escaped_bracket("stem:[[a,b\\]]")]
+    fn stem_macros(#[case] stem_macro: &str) {
+        let markup = format!("See {stem_macro} here");
+        let s = Scanner::new(&markup);
+        let stem_macros: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::StemMacro)
+            .collect();
+        assert_eq!(
+            stem_macros,
+            vec![Token::new_default(
+                TokenType::StemMacro,
+                stem_macro.to_string(),
+                Some(stem_macro.to_string()),
+                1,
+                5,
+                4 + stem_macro.len(),
+            )]
+        );
+    }
+
+    #[test]
+    fn stem_macro_requires_content() {
+        let s = Scanner::new("#^latexmath:[] and stem:[]");
+        assert!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .all(|token| token.token_type() != TokenType::StemMacro)
+        );
+    }
+
+    #[test]
+    fn stem_macro_requires_word_boundary() {
+        let s = Scanner::new("system:[x]");
+        assert!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .all(|token| token.token_type() != TokenType::StemMacro)
+        );
+    }
+
+    #[rstest]
+    #[case::flow("((Big cats))")]
+    #[case::concealed("(((Cats, Big, Lions)))")]
+    #[case::concealed_macro("indexterm:[Cats, Big]")]
+    #[case::flow_macro("indexterm2:[Big cats]")]
+    fn index_terms(#[case] index_term: &str) {
+        let markup = format!("See {index_term} here");
+        let s = Scanner::new(&markup);
+        let index_terms: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::IndexTerm)
+            .collect();
+        assert_eq!(
+            index_terms,
+            vec![Token::new_default(
+                TokenType::IndexTerm,
+                index_term.to_string(),
+                Some(index_term.to_string()),
+                1,
+                5,
+                4 + index_term.len(),
+            )]
+        );
+    }
+
+    #[test]
+    fn index_term_parens_are_text() {
+        let s = Scanner::new("Cats (and dogs) are (C) here");
+        assert!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .all(|token| token.token_type() != TokenType::IndexTerm)
+        );
+    }
+
+    #[rstest]
+    #[case::constrained("+<b>cats</b>+")]
+    #[case::unconstrained("++<b>cats</b>++")]
+    #[case::triple_plus("+++<b>cats</b>+++")]
+    #[case::legacy("$$<b>cats</b>$$")]
+    fn inline_passthroughs(#[case] passthrough: &str) {
+        let markup = format!("See {passthrough} here");
+        let s = Scanner::new(&markup);
+        let passthroughs: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::InlinePassthrough)
+            .collect();
+        assert_eq!(
+            passthroughs,
+            vec![Token::new_default(
+                TokenType::InlinePassthrough,
+                passthrough.to_string(),
+                Some(passthrough.to_string()),
+                1,
+                5,
+                4 + passthrough.len(),
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::in_word("A C+ or B+ grade")]
+    #[case::spaces("1 + 2 + 3")]
+    #[case::single_dollar("Costs $5 or $10")]
+    fn inline_passthrough_chars_are_text(#[case] markup: &str) {
+        let s = Scanner::new(markup);
+        assert!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .all(|token| token.token_type() != TokenType::InlinePassthrough)
+        );
+    }
+
+    #[rstest]
+    #[case::constrained("\\*not bold*")]
+    #[case::unconstrained("\\**not bold**")]
+    #[case::monospace("\\`not code`")]
+    #[case::unclosed("\\*")]
+    #[case::attribute_reference("\\{attr}")]
+    #[case::cross_reference("\\<<not-xref>>")]
+    #[case::url("\\https://example.com")]
+    #[case::url_with_text("\\https://example.com[Example]")]
+    #[case::inline_macro("\\kbd:[Ctrl+T]")]
+    #[case::charref("\\&amp;")]
+    fn backslash_escapes(#[case] escaped: &str) {
+        let markup = format!("See {escaped} here");
+        let s = Scanner::new(&markup);
+        let escaped_tokens: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.lexeme.starts_with('\\'))
+            .collect();
+        assert_eq!(
+            escaped_tokens,
+            vec![Token::new_default(
+                TokenType::Text,
+                escaped.to_string(),
+                Some(escaped[1..].to_string()),
+                1,
+                5,
+                4 + escaped.len(),
+            )]
+        );
+    }
+
+    #[test]
+    fn backslash_escaped_include() {
+        let s = Scanner::new("\\include::foo.adoc[]\n");
+        let first = s.into_iter().next().unwrap().unwrap();
+        assert_eq!(first.token_type(), TokenType::Text);
+        assert_eq!(first.text(), "include::foo.adoc[]");
+    }
+
+    #[test]
+    fn unescaping_backslash_is_text() {
+        let s = Scanner::new("C:\\Users\\cats");
+        assert_eq!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .filter(|token| token.token_type() == TokenType::Text)
+                .map(|token| token.text())
+                .collect::<String>(),
+            "C:\\Users\\cats"
+        );
+    }
+
+    #[rstest]
+    #[case::document_start("  literal text", 1)]
+    #[case::after_blank_line("Some text.\n\n  literal text", 3)]
+    #[case::after_attribute_list("Some text.\n\n[indent=2]\n\tliteral text", 4)]
+    fn literal_paragraphs(#[case] markup: &str, #[case] line: usize) {
+        let s = Scanner::new(markup);
+        let literal_paragraphs: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::LiteralParagraph)
+            .collect();
+        assert_eq!(literal_paragraphs.len(), 1);
+        assert_eq!(literal_paragraphs[0].line, line);
+    }
+
+    #[rstest]
+    #[case::continued_line("Some text\n  continued")]
+    #[case::list_item("Some text.\n\n  * list item")]
+    #[case::ordered_list_item("Some text.\n\n  1. list item")]
+    #[case::description_list("Some text.\n\n  Term:: definition")]
+    #[case::blank_line("Some text.\n\n  \nMore text")]
+    fn not_literal_paragraphs(#[case] markup: &str) {
+        let s = Scanner::new(markup);
+        assert!(
+            s.into_iter()
+                .filter_map(|result| result.ok())
+                .all(|token| token.token_type() != TokenType::LiteralParagraph)
+        );
+    }
+
+    #[rstest]
+    #[case::no_domain("cats@dogs")]
+    #[case::in_word("meow.cats@dogs")]
+    #[case::in_url("https://cats@dogs.foo/path")]
+    fn not_email(#[case] markup: &str) {
+        let s = Scanner::new(markup);
+        assert!(
+            !s.into_iter()
+                .filter_map(|result| result.ok())
+                .any(|token| token.token_type() == TokenType::Email)
+        );
+    }
+
+    #[rstest]
+    #[case::numeric_id("[[0]]")]
+    #[case::nested_brackets("[[1, 2], [3]]")]
+    #[case::space_in_id("[[some id]]")]
+    #[case::unclosed("[[foo")]
+    fn not_inline_anchor(#[case] markup: &str) {
+        let markup = format!("An array {markup} stays");
+        let s = Scanner::new(&markup);
+        assert!(
+            !s.into_iter()
+                .filter_map(|result| result.ok())
+                .any(|token| token.token_type() == TokenType::InlineAnchor)
+        );
+    }
+
+    #[rstest]
+    #[case::bibliography("[[[foo]]]")]
+    #[case::bibliography_with_label("[[[foo,Foo]]]")]
+    fn bibliography_anchor(#[case] anchor: &str) {
+        let markup = format!("* {anchor} Some text");
+        let s = Scanner::new(&markup);
+        let anchors: Vec<Token> = s
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::InlineAnchor)
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![Token::new_default(
+                TokenType::InlineAnchor,
+                anchor.to_string(),
+                Some(anchor.to_string()),
+                1,
+                3,
+                2 + anchor.len(),
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::mid_line("* Some [[[foo]]] text")]
+    #[case::not_list_item("[[[foo]]] Some text")]
+    #[case::in_code("* `grid[[[0]]]`")]
+    #[case::nested_brackets("* [[[1], [2]]]")]
+    fn not_bibliography_anchor(#[case] markup: &str) {
+        let s = Scanner::new(markup);
+        assert!(
+            !s.into_iter()
+                .filter_map(|result| result.ok())
+                .any(|token| token.text().starts_with("[[["))
+        );
+    }
+
+    #[test]
+    fn inline_anchor_not_block_anchor() {
+        let s = Scanner::new("[[foo]]Some text");
+        let first = s.into_iter().next().unwrap().unwrap();
+        assert_eq!(first.token_type(), TokenType::InlineAnchor);
+    }
+
     #[test]
     fn simple_table() {
         let markup = "[cols=\"1,1\"]\n|===\n|cell one\n|cell two\n|===";
@@ -2251,6 +3060,45 @@ mod tests {
         scan_and_assert_eq(markup, expected_tokens);
     }
 
+    #[rstest]
+    #[case::audio("audio::file.mp3[]", TokenType::BlockAudioMacro)]
+    #[case::video("video::file.mp4[poster.png, 640, 360]\n", TokenType::BlockVideoMacro)]
+    #[case::youtube("video::RvRhUHTV_8k[youtube]", TokenType::BlockVideoMacro)]
+    #[case::not_alone("video::file.mp4[] and more", TokenType::Text)]
+    #[case::inline("See video::file.mp4[]", TokenType::Text)]
+    fn block_media_macros(#[case] markup: &str, #[case] expected: TokenType) {
+        let mut s = Scanner::new(markup);
+        assert_eq!(s.next().unwrap().unwrap().token_type(), expected);
+    }
+
+    #[rstest]
+    #[case::numbered("foo // <1>\n", vec!["<1>"])]
+    #[case::auto_numbered("foo # <.>\n", vec!["<.>"])]
+    #[case::several("foo <1> <2>\n", vec!["<1>", "<2>"])]
+    #[case::xml_comment("<foo/> <!--1-->\n", vec!["<!--1-->"])]
+    #[case::at_end("foo <1>", vec!["<1>"])]
+    #[case::not_last("foo <1> bar\n", vec![])]
+    #[case::not_a_number("Vec<T>\n", vec![])]
+    fn code_callouts(#[case] markup: &str, #[case] expected: Vec<&str>) {
+        let s = Scanner::new(markup);
+        let callouts: Vec<String> = s
+            .filter_map(|result| result.ok())
+            .filter(|token| token.token_type() == TokenType::CodeCallout)
+            .map(|token| token.lexeme)
+            .collect();
+        assert_eq!(callouts, expected);
+    }
+
+    #[rstest]
+    #[case::numbered("<1> Bar", "<1> ")]
+    #[case::auto_numbered("<.> Bar", "<.> ")]
+    fn code_callout_list_items(#[case] markup: &str, #[case] expected: &str) {
+        let mut s = Scanner::new(markup);
+        let token = s.next().unwrap().unwrap();
+        assert_eq!(token.token_type(), TokenType::CodeCalloutListItem);
+        assert_eq!(token.lexeme, expected);
+    }
+
     #[test]
     fn typographers_apostrophe() {
         let markup = "Sam's ";
@@ -139,6 +139,7 @@ impl Token {
             | TokenType::UnconstrainedMonospace
             | TokenType::UnconstrainedMark
             | TokenType::CharRef
+            | TokenType::InlinePassthrough
             | TokenType::InlineStyle
             | TokenType::InlineMacroClose)
     }
@@ -146,7 +147,7 @@ impl Token {
     pub fn can_be_in_document_header(&self) -> bool {
         matches!(
             self.token_type(),
-            TokenType::Heading1 | TokenType::Attribute
+            TokenType::Heading1 | TokenType::Attribute | TokenType::Email
         ) || self.is_inline()
     }
 
@@ -164,9 +165,19 @@ impl Token {
     /// Charref entities, following asciidoctor
     pub fn validate(&mut self) {
         match self.token_type() {
-            TokenType::BlockAnchor | TokenType::CrossReference => {
+            TokenType::BlockAnchor
                 // no spaces or newlines inside
-                if self.lexeme.contains(' ') || self.lexeme.contains('\n') {
+                if (self.lexeme.contains(' ') || self.lexeme.contains('\n')) => {
+                    self.token_type = TokenType::Text
+                }
+            TokenType::CrossReference => {
+                // no spaces or newlines in the target, though the text may have spaces
+                let target = self
+                    .lexeme
+                    .split([',', '['])
+                    .next()
+                    .unwrap_or(&self.lexeme);
+                if target.contains(' ') || self.lexeme.contains('\n') {
                     self.token_type = TokenType::Text
                 }
             }
@@ -246,6 +257,7 @@ pub enum TokenType {
     WarningPara,   // WARNING:
 
     BlockContinuation, // a "+" all by itself on a line can signal continuation
+    LiteralParagraph,  // the indentation of a line starting a paragraph, e.g., "  literal text"
 
     // Tables -- note that we'll do two passes here for simplicity in parsing: just collect the
     // cells and we will handle rows/inlines later
@@ -257,7 +269,6 @@ pub enum TokenType {
     Strong,   // TK Handle bounded characters, e.g., **Some**thing -> <b>Some</b>thing
     Emphasis, // same applies above
     Monospace,
-    Literal,
     Mark, // #text# or [.class]#text#
 
     Superscript, // ^super^
@@ -267,16 +278,23 @@ pub enum TokenType {
     UnconstrainedStrong, // TK Handle bounded characters, e.g., **Some**thing -> <b>Some</b>thing
     UnconstrainedEmphasis, // same applies above
     UnconstrainedMonospace,
-    UnconstrainedLiteral,
     UnconstrainedMark, // #text# or [.class]#text#
 
     // inline macros
     BlockImageMacro,
+    BlockAudioMacro, // audio::file.mp3[]
+    BlockVideoMacro, // video::file.mp4[], video::id[youtube]
     InlineImageMacro,
     LinkMacro,
     FootnoteMacro, // requires a second pass? OR: do some kind of `self.last_token` check on the
-    PassthroughInlineMacro,
+    PassthroughInlineMacro, // pass:[raw], or pass:q,a[with substitutions]
+    InlinePassthrough, // +text+, ++text++, +++raw+++, or $$raw$$
     InlineMacroClose,
+    KeyboardMacro, // kbd:[Ctrl+T]
+    ButtonMacro,   // btn:[Save]
+    MenuMacro,     // menu:File[Save As]
+    StemMacro,     // stem:[sqrt(4) = 2], latexmath:[...], asciimath:[...]
+    IndexTerm,     // ((flow term)), (((concealed, term))), indexterm:[...], indexterm2:[...]
 
     // garden-variety text
     Hyperlink, // http://whatever.txt
@@ -305,6 +323,7 @@ pub enum TokenType {
 
     // Attributes, anchors and references
     BlockAnchor,
+    InlineAnchor,      // [[some_id]] or anchor:some_id[reftext] mid-paragraph
     ElementAttributes, // any of: [quote], [quote], [role="foo"], [#foo], etc.
     CrossReference,
     Attribute,
@@ -333,6 +352,11 @@ impl TokenType {
                 | TokenType::OpenBlock
                 | TokenType::QuoteVerseBlock
                 | TokenType::ExampleBlock
+                | TokenType::NotePara
+                | TokenType::TipPara
+                | TokenType::ImportantPara
+                | TokenType::CautionPara
+                | TokenType::WarningPara
         )
     }
 }
@@ -351,6 +375,23 @@ mod tests {
         assert_eq!(token.token_type(), TokenType::Text)
     }
 
+    #[rstest]
+    #[case("<<foo,Some text>>")]
+    #[case("xref:other.adoc#foo[Some text]")]
+    fn space_in_xref_text_is_valid(#[case] lexeme: &str) {
+        let mut token = Token::new(
+            TokenType::CrossReference,
+            lexeme.to_string(),
+            None,
+            1,
+            1,
+            1,
+            vec![],
+        );
+        token.validate();
+        assert_eq!(token.token_type(), TokenType::CrossReference)
+    }
+
     #[rstest]
     #[case("foo")]
     #[case("longer_than_foo")]
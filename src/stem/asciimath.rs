@@ -0,0 +1,561 @@
+//! An AsciiMath parser following the grammar at <https://asciimath.org/#grammar>:
+//!
+//! ```text
+//! S ::= v | lEr | uS | bSS             Simple expression
+//! I ::= S_S | S^S | S_S^S | S          Intermediate expression
+//! E ::= IE | I/I                       Expression
+//! ```
+use super::{MathNode, MathVariant, row, scripted};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unary {
+    Sqrt,
+    Style(MathVariant),
+    Accent(&'static str, bool),
+    Fence(&'static str, &'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Binary {
+    Frac,
+    Root,
+    Overset,
+    Underset,
+    Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Symbol {
+    Ident(&'static str),
+    Op(&'static str),
+    /// Rendered as (padded) text, e.g., "and"
+    Text(&'static str),
+    Space(f32),
+    Left(&'static str),
+    Right(&'static str),
+    /// "|" and "||", which may either fence an expression or stand alone
+    Bar(&'static str),
+    Sub,
+    Sup,
+    Slash,
+    Unary(Unary),
+    Binary(Binary),
+}
+
+#[rustfmt::skip]
+static SYMBOLS: &[(&str, Symbol)] = &[
+    // greek letters
+    ("alpha", Symbol::Ident("α")), ("beta", Symbol::Ident("β")), ("chi", Symbol::Ident("χ")),
+    ("delta", Symbol::Ident("δ")), ("Delta", Symbol::Ident("Δ")), ("epsi", Symbol::Ident("ε")),
+    ("epsilon", Symbol::Ident("ε")), ("varepsilon", Symbol::Ident("ɛ")), ("eta", Symbol::Ident("η")),
+    ("gamma", Symbol::Ident("γ")), ("Gamma", Symbol::Ident("Γ")), ("iota", Symbol::Ident("ι")),
+    ("kappa", Symbol::Ident("κ")), ("lambda", Symbol::Ident("λ")), ("Lambda", Symbol::Ident("Λ")),
+    ("lamda", Symbol::Ident("λ")), ("Lamda", Symbol::Ident("Λ")), ("mu", Symbol::Ident("μ")),
+    ("nu", Symbol::Ident("ν")), ("omega", Symbol::Ident("ω")), ("Omega", Symbol::Ident("Ω")),
+    ("phi", Symbol::Ident("ϕ")), ("varphi", Symbol::Ident("φ")), ("Phi", Symbol::Ident("Φ")),
+    ("pi", Symbol::Ident("π")), ("Pi", Symbol::Ident("Π")), ("psi", Symbol::Ident("ψ")),
+    ("Psi", Symbol::Ident("Ψ")), ("rho", Symbol::Ident("ρ")), ("sigma", Symbol::Ident("σ")),
+    ("Sigma", Symbol::Ident("Σ")), ("tau", Symbol::Ident("τ")), ("theta", Symbol::Ident("θ")),
+    ("vartheta", Symbol::Ident("ϑ")), ("Theta", Symbol::Ident("Θ")), ("upsilon", Symbol::Ident("υ")),
+    ("xi", Symbol::Ident("ξ")), ("Xi", Symbol::Ident("Ξ")), ("zeta", Symbol::Ident("ζ")),
+    // operation symbols
+    ("+", Symbol::Op("+")), ("-", Symbol::Op("−")), ("*", Symbol::Op("⋅")), ("cdot", Symbol::Op("⋅")),
+    ("**", Symbol::Op("∗")), ("ast", Symbol::Op("∗")), ("***", Symbol::Op("⋆")), ("star", Symbol::Op("⋆")),
+    ("//", Symbol::Op("/")), ("\\\\", Symbol::Op("\\")), ("setminus", Symbol::Op("\\")),
+    ("xx", Symbol::Op("×")), ("times", Symbol::Op("×")), ("|><", Symbol::Op("⋉")), ("><|", Symbol::Op("⋊")),
+    ("|><|", Symbol::Op("⋈")), ("-:", Symbol::Op("÷")), ("div", Symbol::Op("÷")), ("@", Symbol::Op("∘")),
+    ("circ", Symbol::Op("∘")), ("o+", Symbol::Op("⊕")), ("oplus", Symbol::Op("⊕")), ("ox", Symbol::Op("⊗")),
+    ("otimes", Symbol::Op("⊗")), ("o.", Symbol::Op("⊙")), ("odot", Symbol::Op("⊙")),
+    ("sum", Symbol::Op("∑")), ("prod", Symbol::Op("∏")), ("^^", Symbol::Op("∧")), ("wedge", Symbol::Op("∧")),
+    ("^^^", Symbol::Op("⋀")), ("bigwedge", Symbol::Op("⋀")), ("vv", Symbol::Op("∨")), ("vee", Symbol::Op("∨")),
+    ("vvv", Symbol::Op("⋁")), ("bigvee", Symbol::Op("⋁")), ("nn", Symbol::Op("∩")), ("cap", Symbol::Op("∩")),
+    ("nnn", Symbol::Op("⋂")), ("bigcap", Symbol::Op("⋂")), ("uu", Symbol::Op("∪")), ("cup", Symbol::Op("∪")),
+    ("uuu", Symbol::Op("⋃")), ("bigcup", Symbol::Op("⋃")), (",", Symbol::Op(",")), ("'", Symbol::Op("′")),
+    ("!", Symbol::Op("!")), (";", Symbol::Op(";")), (":", Symbol::Op(":")), (".", Symbol::Op(".")),
+    // relation symbols
+    ("=", Symbol::Op("=")), ("!=", Symbol::Op("≠")), ("ne", Symbol::Op("≠")), (":=", Symbol::Op(":=")),
+    ("<", Symbol::Op("<")), ("lt", Symbol::Op("<")), (">", Symbol::Op(">")), ("gt", Symbol::Op(">")),
+    ("<=", Symbol::Op("≤")), ("le", Symbol::Op("≤")), (">=", Symbol::Op("≥")), ("ge", Symbol::Op("≥")),
+    ("-<", Symbol::Op("≺")), ("prec", Symbol::Op("≺")), (">-", Symbol::Op("≻")), ("succ", Symbol::Op("≻")),
+    ("-<=", Symbol::Op("⪯")), ("preceq", Symbol::Op("⪯")), (">-=", Symbol::Op("⪰")), ("succeq", Symbol::Op("⪰")),
+    ("in", Symbol::Op("∈")), ("!in", Symbol::Op("∉")), ("notin", Symbol::Op("∉")), ("sub", Symbol::Op("⊂")),
+    ("subset", Symbol::Op("⊂")), ("sup", Symbol::Op("⊃")), ("supset", Symbol::Op("⊃")),
+    ("sube", Symbol::Op("⊆")), ("subseteq", Symbol::Op("⊆")), ("supe", Symbol::Op("⊇")),
+    ("supseteq", Symbol::Op("⊇")), ("-=", Symbol::Op("≡")), ("equiv", Symbol::Op("≡")),
+    ("~=", Symbol::Op("≅")), ("cong", Symbol::Op("≅")), ("~~", Symbol::Op("≈")), ("approx", Symbol::Op("≈")),
+    ("~", Symbol::Op("∼")), ("sim", Symbol::Op("∼")), ("prop", Symbol::Op("∝")), ("propto", Symbol::Op("∝")),
+    // logical symbols
+    ("and", Symbol::Text("and")), ("or", Symbol::Text("or")), ("not", Symbol::Op("¬")),
+    ("neg", Symbol::Op("¬")), ("=>", Symbol::Op("⇒")), ("implies", Symbol::Op("⇒")), ("if", Symbol::Text("if")),
+    ("<=>", Symbol::Op("⇔")), ("iff", Symbol::Op("⇔")), ("AA", Symbol::Op("∀")), ("forall", Symbol::Op("∀")),
+    ("EE", Symbol::Op("∃")), ("exists", Symbol::Op("∃")), ("_|_", Symbol::Op("⊥")), ("bot", Symbol::Op("⊥")),
+    ("TT", Symbol::Op("⊤")), ("top", Symbol::Op("⊤")), ("|--", Symbol::Op("⊢")), ("vdash", Symbol::Op("⊢")),
+    ("|==", Symbol::Op("⊨")), ("models", Symbol::Op("⊨")),
+    // grouping brackets
+    ("(", Symbol::Left("(")), (")", Symbol::Right(")")), ("[", Symbol::Left("[")), ("]", Symbol::Right("]")),
+    ("{", Symbol::Left("{")), ("}", Symbol::Right("}")), ("(:", Symbol::Left("⟨")), (":)", Symbol::Right("⟩")),
+    ("<<", Symbol::Left("⟨")), (">>", Symbol::Right("⟩")), ("langle", Symbol::Left("⟨")),
+    ("rangle", Symbol::Right("⟩")), ("{:", Symbol::Left("")), (":}", Symbol::Right("")),
+    ("|", Symbol::Bar("|")), ("||", Symbol::Bar("‖")),
+    // miscellaneous symbols
+    ("int", Symbol::Op("∫")), ("oint", Symbol::Op("∮")), ("del", Symbol::Op("∂")), ("partial", Symbol::Op("∂")),
+    ("grad", Symbol::Op("∇")), ("nabla", Symbol::Op("∇")), ("+-", Symbol::Op("±")), ("pm", Symbol::Op("±")),
+    ("-+", Symbol::Op("∓")), ("mp", Symbol::Op("∓")), ("O/", Symbol::Op("∅")), ("emptyset", Symbol::Op("∅")),
+    ("oo", Symbol::Op("∞")), ("infty", Symbol::Op("∞")), ("aleph", Symbol::Op("ℵ")), ("/_", Symbol::Op("∠")),
+    ("angle", Symbol::Op("∠")), (":.", Symbol::Op("∴")), ("therefore", Symbol::Op("∴")),
+    (":'", Symbol::Op("∵")), ("because", Symbol::Op("∵")), ("...", Symbol::Op("…")), ("ldots", Symbol::Op("…")),
+    ("cdots", Symbol::Op("⋯")), ("vdots", Symbol::Op("⋮")), ("ddots", Symbol::Op("⋱")),
+    ("diamond", Symbol::Op("⋄")), ("square", Symbol::Op("□")), ("|__", Symbol::Left("⌊")),
+    ("__|", Symbol::Right("⌋")), ("lfloor", Symbol::Left("⌊")), ("rfloor", Symbol::Right("⌋")),
+    ("|~", Symbol::Left("⌈")), ("~|", Symbol::Right("⌉")), ("lceiling", Symbol::Left("⌈")),
+    ("rceiling", Symbol::Right("⌉")), ("CC", Symbol::Ident("ℂ")), ("NN", Symbol::Ident("ℕ")),
+    ("QQ", Symbol::Ident("ℚ")), ("RR", Symbol::Ident("ℝ")), ("ZZ", Symbol::Ident("ℤ")),
+    ("\\ ", Symbol::Space(0.278)), ("quad", Symbol::Space(1.0)), ("qquad", Symbol::Space(2.0)),
+    // standard functions
+    ("sin", Symbol::Ident("sin")), ("cos", Symbol::Ident("cos")), ("tan", Symbol::Ident("tan")),
+    ("sec", Symbol::Ident("sec")), ("csc", Symbol::Ident("csc")), ("cot", Symbol::Ident("cot")),
+    ("arcsin", Symbol::Ident("arcsin")), ("arccos", Symbol::Ident("arccos")),
+    ("arctan", Symbol::Ident("arctan")), ("sinh", Symbol::Ident("sinh")), ("cosh", Symbol::Ident("cosh")),
+    ("tanh", Symbol::Ident("tanh")), ("sech", Symbol::Ident("sech")), ("csch", Symbol::Ident("csch")),
+    ("coth", Symbol::Ident("coth")), ("exp", Symbol::Ident("exp")), ("log", Symbol::Ident("log")),
+    ("ln", Symbol::Ident("ln")), ("det", Symbol::Ident("det")), ("dim", Symbol::Ident("dim")),
+    ("mod", Symbol::Ident("mod")), ("gcd", Symbol::Ident("gcd")), ("lcm", Symbol::Ident("lcm")),
+    ("lub", Symbol::Ident("lub")), ("glb", Symbol::Ident("glb")), ("min", Symbol::Ident("min")),
+    ("max", Symbol::Ident("max")), ("lim", Symbol::Ident("lim")), ("Lim", Symbol::Ident("Lim")),
+    // arrows
+    ("uarr", Symbol::Op("↑")), ("uparrow", Symbol::Op("↑")), ("darr", Symbol::Op("↓")),
+    ("downarrow", Symbol::Op("↓")), ("rarr", Symbol::Op("→")), ("rightarrow", Symbol::Op("→")),
+    ("->", Symbol::Op("→")), ("to", Symbol::Op("→")), (">->", Symbol::Op("↣")), ("->>", Symbol::Op("↠")),
+    (">->>", Symbol::Op("⤖")), ("|->", Symbol::Op("↦")), ("mapsto", Symbol::Op("↦")),
+    ("larr", Symbol::Op("←")), ("leftarrow", Symbol::Op("←")), ("harr", Symbol::Op("↔")),
+    ("leftrightarrow", Symbol::Op("↔")), ("rArr", Symbol::Op("⇒")), ("Rightarrow", Symbol::Op("⇒")),
+    ("lArr", Symbol::Op("⇐")), ("Leftarrow", Symbol::Op("⇐")), ("hArr", Symbol::Op("⇔")),
+    ("Leftrightarrow", Symbol::Op("⇔")),
+    // scripts and division
+    ("_", Symbol::Sub), ("^", Symbol::Sup), ("/", Symbol::Slash),
+    // unary and binary commands
+    ("sqrt", Symbol::Unary(Unary::Sqrt)), ("hat", Symbol::Unary(Unary::Accent("^", false))),
+    ("bar", Symbol::Unary(Unary::Accent("¯", false))), ("overline", Symbol::Unary(Unary::Accent("¯", false))),
+    ("vec", Symbol::Unary(Unary::Accent("→", false))), ("dot", Symbol::Unary(Unary::Accent(".", false))),
+    ("ddot", Symbol::Unary(Unary::Accent("..", false))), ("tilde", Symbol::Unary(Unary::Accent("~", false))),
+    ("ul", Symbol::Unary(Unary::Accent("_", true))), ("underline", Symbol::Unary(Unary::Accent("_", true))),
+    ("ubrace", Symbol::Unary(Unary::Accent("⏟", true))), ("underbrace", Symbol::Unary(Unary::Accent("⏟", true))),
+    ("obrace", Symbol::Unary(Unary::Accent("⏞", false))), ("overbrace", Symbol::Unary(Unary::Accent("⏞", false))),
+    ("abs", Symbol::Unary(Unary::Fence("|", "|"))), ("norm", Symbol::Unary(Unary::Fence("‖", "‖"))),
+    ("floor", Symbol::Unary(Unary::Fence("⌊", "⌋"))), ("ceil", Symbol::Unary(Unary::Fence("⌈", "⌉"))),
+    ("bb", Symbol::Unary(Unary::Style(MathVariant::Bold))), ("mathbf", Symbol::Unary(Unary::Style(MathVariant::Bold))),
+    ("bbb", Symbol::Unary(Unary::Style(MathVariant::DoubleStruck))),
+    ("mathbb", Symbol::Unary(Unary::Style(MathVariant::DoubleStruck))),
+    ("cc", Symbol::Unary(Unary::Style(MathVariant::Script))), ("mathcal", Symbol::Unary(Unary::Style(MathVariant::Script))),
+    ("tt", Symbol::Unary(Unary::Style(MathVariant::Monospace))), ("mathtt", Symbol::Unary(Unary::Style(MathVariant::Monospace))),
+    ("fr", Symbol::Unary(Unary::Style(MathVariant::Fraktur))), ("mathfrak", Symbol::Unary(Unary::Style(MathVariant::Fraktur))),
+    ("sf", Symbol::Unary(Unary::Style(MathVariant::SansSerif))), ("mathsf", Symbol::Unary(Unary::Style(MathVariant::SansSerif))),
+    ("frac", Symbol::Binary(Binary::Frac)), ("root", Symbol::Binary(Binary::Root)),
+    ("stackrel", Symbol::Binary(Binary::Overset)), ("overset", Symbol::Binary(Binary::Overset)),
+    ("underset", Symbol::Binary(Binary::Underset)), ("color", Symbol::Binary(Binary::Color)),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Symbol(Symbol),
+    Number(String),
+    Identifier(String),
+    Text(String),
+    Operator(String),
+}
+
+/// What ends an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stop {
+    End,
+    Right,
+    Bar(&'static str),
+}
+
+/// Parses an AsciiMath expression
+pub fn parse(source: &str) -> MathNode {
+    let mut parser = AsciiMathParser {
+        tokens: tokenize(source),
+        position: 0,
+    };
+    row(parser.expression(Stop::End))
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        // quoted text
+        if c == '"' {
+            let (text, remainder) = match rest[1..].find('"') {
+                Some(end) => (&rest[1..end + 1], &rest[end + 2..]),
+                None => (&rest[1..], ""),
+            };
+            tokens.push(Token::Text(text.to_string()));
+            rest = remainder;
+            continue;
+        }
+        // text(...) and mbox(...)
+        if let Some(after) = rest
+            .strip_prefix("text(")
+            .or_else(|| rest.strip_prefix("mbox("))
+        {
+            let (text, remainder) = match after.find(')') {
+                Some(end) => (&after[..end], &after[end + 1..]),
+                None => (after, ""),
+            };
+            tokens.push(Token::Text(text.to_string()));
+            rest = remainder;
+            continue;
+        }
+        // symbols, longest match first
+        if let Some((input, symbol)) = SYMBOLS
+            .iter()
+            .filter(|(input, _)| rest.starts_with(input))
+            .max_by_key(|(input, _)| input.len())
+        {
+            tokens.push(Token::Symbol(*symbol));
+            rest = &rest[input.len()..];
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let mut end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if rest[end..].starts_with('.')
+                && rest[end + 1..].starts_with(|c: char| c.is_ascii_digit())
+            {
+                end += 1 + rest[end + 1..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len() - end - 1);
+            }
+            tokens.push(Token::Number(rest[..end].to_string()));
+            rest = &rest[end..];
+            continue;
+        }
+        if c.is_alphabetic() {
+            tokens.push(Token::Identifier(c.to_string()));
+        } else {
+            tokens.push(Token::Operator(c.to_string()));
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    tokens
+}
+
+struct AsciiMathParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl AsciiMathParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn stops(&self, stop: Stop) -> bool {
+        match (self.peek(), stop) {
+            (None, _) => true,
+            (Some(Token::Symbol(Symbol::Right(_))), Stop::Right) => true,
+            (Some(Token::Symbol(Symbol::Bar(bar))), Stop::Bar(closing)) => *bar == closing,
+            _ => false,
+        }
+    }
+
+    /// E ::= IE | I/I
+    fn expression(&mut self, stop: Stop) -> Vec<MathNode> {
+        let mut nodes = vec![];
+        while !self.stops(stop) {
+            let node = self.intermediate();
+            if let Some(Token::Symbol(Symbol::Slash)) = self.peek() {
+                self.position += 1;
+                let denominator = self.intermediate();
+                nodes.push(MathNode::Fraction(
+                    Box::new(unbracket(node)),
+                    Box::new(unbracket(denominator)),
+                ));
+            } else {
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+
+    /// I ::= S_S | S^S | S_S^S | S
+    fn intermediate(&mut self) -> MathNode {
+        let base = self.simple();
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            match self.peek() {
+                Some(Token::Symbol(Symbol::Sub)) if sub.is_none() => {
+                    self.position += 1;
+                    sub = Some(unbracket(self.simple()));
+                }
+                Some(Token::Symbol(Symbol::Sup)) if sup.is_none() => {
+                    self.position += 1;
+                    sup = Some(unbracket(self.simple()));
+                }
+                _ => break,
+            }
+        }
+        scripted(base, sub, sup)
+    }
+
+    /// S ::= v | lEr | uS | bSS
+    fn simple(&mut self) -> MathNode {
+        let Some(token) = self.next() else {
+            return MathNode::Row(vec![]);
+        };
+        match token {
+            Token::Number(number) => MathNode::Number(number),
+            Token::Identifier(identifier) => MathNode::Identifier(identifier),
+            Token::Text(text) => MathNode::Text(text),
+            Token::Operator(operator) => MathNode::Operator(operator),
+            Token::Symbol(symbol) => match symbol {
+                Symbol::Ident(identifier) => MathNode::Identifier(identifier.into()),
+                Symbol::Op(operator) => MathNode::Operator(operator.into()),
+                Symbol::Text(text) => MathNode::Text(format!(" {} ", text)),
+                Symbol::Space(width) => MathNode::Space(width),
+                Symbol::Sub => MathNode::Operator("_".into()),
+                Symbol::Sup => MathNode::Operator("^".into()),
+                Symbol::Slash => MathNode::Operator("/".into()),
+                Symbol::Right(close) => MathNode::Operator(close.into()),
+                Symbol::Left(open) => {
+                    let body = self.expression(Stop::Right);
+                    let close = match self.next() {
+                        Some(Token::Symbol(Symbol::Right(close))) => close,
+                        _ => "",
+                    };
+                    fenced_or_matrix(open, body, close)
+                }
+                Symbol::Bar(bar) => {
+                    if self.has_closing_bar(bar) {
+                        let body = self.expression(Stop::Bar(bar));
+                        self.position += 1;
+                        MathNode::Fenced(bar.into(), Box::new(row(body)), bar.into())
+                    } else {
+                        MathNode::Operator(bar.into())
+                    }
+                }
+                Symbol::Unary(unary) => {
+                    let argument = unbracket(self.simple());
+                    match unary {
+                        Unary::Sqrt => MathNode::Sqrt(Box::new(argument)),
+                        Unary::Style(variant) => MathNode::Styled(variant, Box::new(argument)),
+                        Unary::Accent(mark, under) => MathNode::Accent {
+                            base: Box::new(argument),
+                            mark: mark.into(),
+                            under,
+                        },
+                        Unary::Fence(open, close) => {
+                            MathNode::Fenced(open.into(), Box::new(argument), close.into())
+                        }
+                    }
+                }
+                Symbol::Binary(binary) => {
+                    let first = unbracket(self.simple());
+                    let second = unbracket(self.simple());
+                    match binary {
+                        Binary::Frac => MathNode::Fraction(Box::new(first), Box::new(second)),
+                        Binary::Root => MathNode::Root(Box::new(second), Box::new(first)),
+                        Binary::Overset => MathNode::Over(Box::new(second), Box::new(first)),
+                        Binary::Underset => MathNode::Under(Box::new(second), Box::new(first)),
+                        Binary::Color => second,
+                    }
+                }
+            },
+        }
+    }
+
+    /// Checks whether a "|" or "||" is closed later on at the same bracket depth
+    fn has_closing_bar(&self, bar: &str) -> bool {
+        let mut depth = 0;
+        for token in &self.tokens[self.position..] {
+            match token {
+                Token::Symbol(Symbol::Left(_)) => depth += 1,
+                Token::Symbol(Symbol::Right(_)) if depth == 0 => return false,
+                Token::Symbol(Symbol::Right(_)) => depth -= 1,
+                Token::Symbol(Symbol::Bar(closing)) if depth == 0 && *closing == bar => {
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+/// Removes the brackets around arguments, e.g., the parentheses in "frac(a+b)(c)"
+fn unbracket(node: MathNode) -> MathNode {
+    match node {
+        MathNode::Fenced(open, body, close)
+            if ["(", "[", "{", ""].contains(&open.as_str())
+                && [")", "]", "}", ""].contains(&close.as_str())
+                && !matches!(*body, MathNode::Matrix(_)) =>
+        {
+            *body
+        }
+        _ => node,
+    }
+}
+
+/// Produces a matrix if the bracketed body is a comma-separated list of bracketed rows with the
+/// same number of columns, e.g., "[(a,b),(c,d)]", and a fenced expression otherwise
+fn fenced_or_matrix(open: &str, body: Vec<MathNode>, close: &str) -> MathNode {
+    let mut rows: Vec<Vec<MathNode>> = vec![];
+    let mut is_matrix = body.len() >= 3;
+    for (idx, node) in body.iter().enumerate() {
+        if idx % 2 == 1 {
+            if node != &MathNode::Operator(",".into()) {
+                is_matrix = false;
+                break;
+            }
+            continue;
+        }
+        match node {
+            MathNode::Fenced(row_open, row_body, _) if ["(", "["].contains(&row_open.as_str()) => {
+                let cells = match row_body.as_ref() {
+                    MathNode::Row(nodes) => nodes.clone(),
+                    other => vec![other.clone()],
+                };
+                let columns: Vec<MathNode> = cells
+                    .split(|cell| cell == &MathNode::Operator(",".into()))
+                    .map(|cell| row(cell.to_vec()))
+                    .collect();
+                if rows
+                    .first()
+                    .is_some_and(|first| first.len() != columns.len())
+                {
+                    is_matrix = false;
+                    break;
+                }
+                rows.push(columns);
+            }
+            _ => {
+                is_matrix = false;
+                break;
+            }
+        }
+    }
+    if is_matrix && body.len() % 2 == 1 {
+        MathNode::Fenced(open.into(), Box::new(MathNode::Matrix(rows)), close.into())
+    } else {
+        MathNode::Fenced(open.into(), Box::new(row(body)), close.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ident(value: &str) -> MathNode {
+        MathNode::Identifier(value.into())
+    }
+
+    fn num(value: &str) -> MathNode {
+        MathNode::Number(value.into())
+    }
+
+    fn op(value: &str) -> MathNode {
+        MathNode::Operator(value.into())
+    }
+
+    #[test]
+    fn simple_expression() {
+        assert_eq!(
+            parse("x + 1.5 = alpha"),
+            MathNode::Row(vec![ident("x"), op("+"), num("1.5"), op("="), ident("α")])
+        )
+    }
+
+    #[test]
+    fn fraction_removes_brackets() {
+        assert_eq!(
+            parse("(a+b)/2"),
+            MathNode::Fraction(
+                Box::new(MathNode::Row(vec![ident("a"), op("+"), ident("b")])),
+                Box::new(num("2"))
+            )
+        );
+        assert_eq!(parse("frac(a)(b)"), parse("a/b"))
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(
+            parse("x_i^2"),
+            MathNode::SubSup(
+                Box::new(ident("x")),
+                Box::new(ident("i")),
+                Box::new(num("2"))
+            )
+        );
+        assert_eq!(
+            parse("sum_(n=1)^oo"),
+            MathNode::UnderOver(
+                Box::new(op("∑")),
+                Box::new(MathNode::Row(vec![ident("n"), op("="), num("1")])),
+                Box::new(op("∞"))
+            )
+        )
+    }
+
+    #[test]
+    fn longest_symbol_match() {
+        assert_eq!(
+            parse("x <= y"),
+            MathNode::Row(vec![ident("x"), op("≤"), ident("y")])
+        );
+        assert_eq!(parse("sinx"), MathNode::Row(vec![ident("sin"), ident("x")]))
+    }
+
+    #[test]
+    fn matrix() {
+        assert_eq!(
+            parse("[(1,0),(0,1)]"),
+            MathNode::Fenced(
+                "[".into(),
+                Box::new(MathNode::Matrix(vec![
+                    vec![num("1"), num("0")],
+                    vec![num("0"), num("1")]
+                ])),
+                "]".into()
+            )
+        )
+    }
+
+    #[test]
+    fn absolute_value_bars() {
+        assert_eq!(
+            parse("|x|"),
+            MathNode::Fenced("|".into(), Box::new(ident("x")), "|".into())
+        );
+        assert_eq!(
+            parse("a | b"),
+            MathNode::Row(vec![ident("a"), op("|"), ident("b")])
+        )
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            parse("x \"if\" text(y > 0)"),
+            MathNode::Row(vec![
+                ident("x"),
+                MathNode::Text("if".into()),
+                MathNode::Text("y > 0".into())
+            ])
+        )
+    }
+}
@@ -0,0 +1,527 @@
+//! A parser for a practical subset of LaTeX math: fractions, roots, scripts, limits, accents,
+//! fonts, `\left`/`\right` delimiters, matrix-like environments, and the common symbols.
+use super::{MathNode, MathVariant, row, scripted};
+
+#[rustfmt::skip]
+static SYMBOLS: &[(&str, &str)] = &[
+    // greek letters (identifiers)
+    ("alpha", "α"), ("beta", "β"), ("gamma", "γ"), ("delta", "δ"), ("epsilon", "ϵ"),
+    ("varepsilon", "ε"), ("zeta", "ζ"), ("eta", "η"), ("theta", "θ"), ("vartheta", "ϑ"),
+    ("iota", "ι"), ("kappa", "κ"), ("lambda", "λ"), ("mu", "μ"), ("nu", "ν"), ("xi", "ξ"),
+    ("pi", "π"), ("varpi", "ϖ"), ("rho", "ρ"), ("varrho", "ϱ"), ("sigma", "σ"), ("varsigma", "ς"),
+    ("tau", "τ"), ("upsilon", "υ"), ("phi", "ϕ"), ("varphi", "φ"), ("chi", "χ"), ("psi", "ψ"),
+    ("omega", "ω"), ("Gamma", "Γ"), ("Delta", "Δ"), ("Theta", "Θ"), ("Lambda", "Λ"), ("Xi", "Ξ"),
+    ("Pi", "Π"), ("Sigma", "Σ"), ("Upsilon", "Υ"), ("Phi", "Φ"), ("Psi", "Ψ"), ("Omega", "Ω"),
+    ("ell", "ℓ"), ("hbar", "ℏ"), ("aleph", "ℵ"), ("Re", "ℜ"), ("Im", "ℑ"), ("wp", "℘"),
+];
+
+#[rustfmt::skip]
+static OPERATORS: &[(&str, &str)] = &[
+    ("times", "×"), ("cdot", "⋅"), ("div", "÷"), ("pm", "±"), ("mp", "∓"), ("ast", "∗"),
+    ("star", "⋆"), ("circ", "∘"), ("bullet", "∙"), ("oplus", "⊕"), ("ominus", "⊖"),
+    ("otimes", "⊗"), ("odot", "⊙"), ("setminus", "∖"), ("wedge", "∧"), ("land", "∧"),
+    ("vee", "∨"), ("lor", "∨"), ("cap", "∩"), ("cup", "∪"), ("neg", "¬"), ("lnot", "¬"),
+    ("leq", "≤"), ("le", "≤"), ("geq", "≥"), ("ge", "≥"), ("neq", "≠"), ("ne", "≠"),
+    ("ll", "≪"), ("gg", "≫"), ("approx", "≈"), ("equiv", "≡"), ("sim", "∼"), ("simeq", "≃"),
+    ("cong", "≅"), ("propto", "∝"), ("prec", "≺"), ("succ", "≻"), ("preceq", "⪯"),
+    ("succeq", "⪰"), ("in", "∈"), ("notin", "∉"), ("ni", "∋"), ("subset", "⊂"), ("supset", "⊃"),
+    ("subseteq", "⊆"), ("supseteq", "⊇"), ("perp", "⊥"), ("parallel", "∥"), ("mid", "∣"),
+    ("vdash", "⊢"), ("models", "⊨"), ("forall", "∀"), ("exists", "∃"), ("nexists", "∄"),
+    ("partial", "∂"), ("nabla", "∇"), ("infty", "∞"), ("emptyset", "∅"), ("varnothing", "∅"),
+    ("angle", "∠"), ("therefore", "∴"), ("because", "∵"), ("ldots", "…"), ("dots", "…"),
+    ("cdots", "⋯"), ("vdots", "⋮"), ("ddots", "⋱"), ("prime", "′"), ("top", "⊤"), ("bot", "⊥"),
+    ("to", "→"), ("rightarrow", "→"), ("leftarrow", "←"), ("gets", "←"),
+    ("leftrightarrow", "↔"), ("Rightarrow", "⇒"), ("implies", "⇒"), ("Leftarrow", "⇐"),
+    ("Leftrightarrow", "⇔"), ("iff", "⇔"), ("mapsto", "↦"), ("uparrow", "↑"),
+    ("downarrow", "↓"), ("longrightarrow", "⟶"), ("longleftarrow", "⟵"),
+    ("Longrightarrow", "⟹"), ("Longleftarrow", "⟸"), ("sum", "∑"), ("prod", "∏"),
+    ("coprod", "∐"), ("bigcup", "⋃"), ("bigcap", "⋂"), ("bigvee", "⋁"), ("bigwedge", "⋀"),
+    ("bigoplus", "⨁"), ("bigotimes", "⨂"), ("int", "∫"), ("iint", "∬"), ("iiint", "∭"),
+    ("oint", "∮"), ("langle", "⟨"), ("rangle", "⟩"), ("lfloor", "⌊"), ("rfloor", "⌋"),
+    ("lceil", "⌈"), ("rceil", "⌉"), ("vert", "|"), ("Vert", "‖"), ("lvert", "|"), ("rvert", "|"),
+    ("lVert", "‖"), ("rVert", "‖"), ("backslash", "\\"), ("{", "{"), ("}", "}"), ("|", "‖"),
+    ("%", "%"), ("$", "$"), ("#", "#"), ("&", "&"), ("_", "_"),
+];
+
+static FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "sec", "csc", "cot", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "coth", "exp", "log", "ln", "lg", "det", "dim", "gcd", "deg", "hom", "ker", "arg", "lim",
+    "liminf", "limsup", "max", "min", "sup", "inf", "Pr",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Command(String),
+    Number(String),
+    Letter(String),
+    Char(String),
+    Open,
+    Close,
+    Sub,
+    Sup,
+    Align,
+    NewRow,
+    Whitespace,
+}
+
+/// Parses a LaTeX math expression
+pub fn parse(source: &str) -> MathNode {
+    let mut parser = LatexParser {
+        tokens: tokenize(source),
+        position: 0,
+    };
+    row(parser.sequence(&[]))
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        idx += 1;
+        match c {
+            '\\' => {
+                if idx < chars.len() && chars[idx] == '\\' {
+                    idx += 1;
+                    tokens.push(Token::NewRow);
+                } else if idx < chars.len() && chars[idx].is_ascii_alphabetic() {
+                    let start = idx;
+                    while idx < chars.len() && chars[idx].is_ascii_alphabetic() {
+                        idx += 1;
+                    }
+                    tokens.push(Token::Command(chars[start..idx].iter().collect()));
+                } else if idx < chars.len() {
+                    tokens.push(Token::Command(chars[idx].to_string()));
+                    idx += 1;
+                }
+            }
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '_' => tokens.push(Token::Sub),
+            '^' => tokens.push(Token::Sup),
+            '&' => tokens.push(Token::Align),
+            c if c.is_whitespace() => {
+                if tokens.last() != Some(&Token::Whitespace) {
+                    tokens.push(Token::Whitespace)
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let start = idx - 1;
+                while idx < chars.len()
+                    && (chars[idx].is_ascii_digit()
+                        || (chars[idx] == '.'
+                            && chars.get(idx + 1).is_some_and(|c| c.is_ascii_digit())))
+                {
+                    idx += 1;
+                }
+                tokens.push(Token::Number(chars[start..idx].iter().collect()));
+            }
+            c if c.is_alphabetic() => tokens.push(Token::Letter(c.to_string())),
+            c => tokens.push(Token::Char(c.to_string())),
+        }
+    }
+    tokens
+}
+
+struct LatexParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl LatexParser {
+    /// The next significant (non-whitespace) token
+    fn peek(&self) -> Option<&Token> {
+        self.tokens
+            .get(self.position..)?
+            .iter()
+            .find(|token| **token != Token::Whitespace)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        while self.tokens.get(self.position) == Some(&Token::Whitespace) {
+            self.position += 1;
+        }
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn at_command(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Command(command)) if command == name)
+    }
+
+    /// Parses nodes until the end of input, a closing brace, or any of the given tokens
+    fn sequence(&mut self, stops: &[Token]) -> Vec<MathNode> {
+        let mut nodes = vec![];
+        while let Some(token) = self.peek() {
+            if *token == Token::Close || stops.contains(token) {
+                break;
+            }
+            if self.at_command("right") || self.at_command("end") {
+                break;
+            }
+            let atom = self.atom();
+            nodes.push(self.scripts(atom));
+        }
+        nodes
+    }
+
+    /// Attaches any following sub- and superscripts (including primes) to a base
+    fn scripts(&mut self, base: MathNode) -> MathNode {
+        let mut sub = None;
+        let mut sup: Option<MathNode> = None;
+        loop {
+            match self.peek() {
+                Some(Token::Sub) if sub.is_none() => {
+                    self.next();
+                    sub = Some(self.argument());
+                }
+                Some(Token::Sup) if sup.is_none() => {
+                    self.next();
+                    sup = Some(self.argument());
+                }
+                Some(Token::Char(c)) if c == "'" => {
+                    self.next();
+                    let prime = MathNode::Operator("′".into());
+                    sup = Some(match sup {
+                        Some(MathNode::Operator(primes)) => MathNode::Operator(primes + "′"),
+                        Some(other) => row(vec![other, prime]),
+                        None => prime,
+                    });
+                }
+                Some(Token::Command(command)) if command == "limits" || command == "nolimits" => {
+                    self.next();
+                }
+                _ => break,
+            }
+        }
+        scripted(base, sub, sup)
+    }
+
+    /// A single argument: either a braced group or a single atom
+    fn argument(&mut self) -> MathNode {
+        match self.peek() {
+            Some(Token::Open) => {
+                self.next();
+                let nodes = self.sequence(&[]);
+                self.expect_close();
+                row(nodes)
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn expect_close(&mut self) {
+        if let Some(Token::Close) = self.peek() {
+            self.next();
+        }
+    }
+
+    /// The raw text of a braced argument, e.g., for `\text{...}`
+    fn raw_argument(&mut self) -> String {
+        let mut text = String::new();
+        if self.peek() != Some(&Token::Open) {
+            if let Some(token) = self.next() {
+                text.push_str(&raw_token(&token));
+            }
+            return text;
+        }
+        self.next();
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(self.position).cloned() {
+            self.position += 1;
+            match token {
+                Token::Open => depth += 1,
+                Token::Close if depth == 0 => break,
+                Token::Close => depth -= 1,
+                _ => {}
+            }
+            text.push_str(&raw_token(&token));
+        }
+        text
+    }
+
+    fn atom(&mut self) -> MathNode {
+        let Some(token) = self.next() else {
+            return MathNode::Row(vec![]);
+        };
+        match token {
+            Token::Number(number) => MathNode::Number(number),
+            Token::Letter(letter) => MathNode::Identifier(letter),
+            Token::Char(c) => MathNode::Operator(match c.as_str() {
+                "-" => "−".to_string(),
+                "*" => "∗".to_string(),
+                "'" => "′".to_string(),
+                _ => c,
+            }),
+            Token::Open => {
+                let nodes = self.sequence(&[]);
+                self.expect_close();
+                row(nodes)
+            }
+            Token::Close => MathNode::Row(vec![]),
+            Token::Sub => MathNode::Operator("_".into()),
+            Token::Sup => MathNode::Operator("^".into()),
+            Token::Align | Token::NewRow | Token::Whitespace => MathNode::Row(vec![]),
+            Token::Command(command) => self.command(&command),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> MathNode {
+        if let Some((_, symbol)) = SYMBOLS.iter().find(|(name, _)| *name == command) {
+            return MathNode::Identifier(symbol.to_string());
+        }
+        if let Some((_, symbol)) = OPERATORS.iter().find(|(name, _)| *name == command) {
+            return MathNode::Operator(symbol.to_string());
+        }
+        if FUNCTIONS.contains(&command) {
+            return MathNode::Identifier(command.to_string());
+        }
+        match command {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                MathNode::Fraction(Box::new(numerator), Box::new(denominator))
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let top = self.argument();
+                let bottom = self.argument();
+                MathNode::Fenced(
+                    "(".into(),
+                    Box::new(MathNode::Matrix(vec![vec![top], vec![bottom]])),
+                    ")".into(),
+                )
+            }
+            "sqrt" => {
+                if let Some(Token::Char(c)) = self.peek()
+                    && c == "["
+                {
+                    self.next();
+                    let index = row(self.sequence(&[Token::Char("]".into())]));
+                    self.next();
+                    let base = self.argument();
+                    MathNode::Root(Box::new(base), Box::new(index))
+                } else {
+                    MathNode::Sqrt(Box::new(self.argument()))
+                }
+            }
+            "text" | "textrm" | "textit" | "textbf" | "mbox" => MathNode::Text(self.raw_argument()),
+            "operatorname" => MathNode::Identifier(self.raw_argument()),
+            "mathrm" | "rm" => self.styled(MathVariant::Normal),
+            "mathbf" | "bf" | "boldsymbol" | "bm" => self.styled(MathVariant::Bold),
+            "mathit" | "it" => self.styled(MathVariant::Italic),
+            "mathbb" => self.styled(MathVariant::DoubleStruck),
+            "mathcal" | "mathscr" => self.styled(MathVariant::Script),
+            "mathfrak" => self.styled(MathVariant::Fraktur),
+            "mathsf" => self.styled(MathVariant::SansSerif),
+            "mathtt" => self.styled(MathVariant::Monospace),
+            "hat" | "widehat" => self.accent("^", false),
+            "bar" | "overline" => self.accent("¯", false),
+            "vec" | "overrightarrow" => self.accent("→", false),
+            "dot" => self.accent(".", false),
+            "ddot" => self.accent("..", false),
+            "tilde" | "widetilde" => self.accent("~", false),
+            "underline" => self.accent("_", true),
+            "overbrace" => self.accent("⏞", false),
+            "underbrace" => self.accent("⏟", true),
+            "overset" | "stackrel" => {
+                let over = self.argument();
+                let base = self.argument();
+                MathNode::Over(Box::new(base), Box::new(over))
+            }
+            "underset" => {
+                let under = self.argument();
+                let base = self.argument();
+                MathNode::Under(Box::new(base), Box::new(under))
+            }
+            "left" => {
+                let open = self.delimiter();
+                let body = self.sequence(&[]);
+                let close = if self.at_command("right") {
+                    self.next();
+                    self.delimiter()
+                } else {
+                    String::new()
+                };
+                MathNode::Fenced(open, Box::new(row(body)), close)
+            }
+            "begin" => self.environment(),
+            "," | "thinspace" => MathNode::Space(0.167),
+            ":" | ">" | "medspace" => MathNode::Space(0.222),
+            ";" | "thickspace" => MathNode::Space(0.278),
+            " " => MathNode::Space(0.278),
+            "quad" => MathNode::Space(1.0),
+            "qquad" => MathNode::Space(2.0),
+            "!" => MathNode::Row(vec![]),
+            "displaystyle" | "textstyle" | "limits" | "nolimits" | "big" | "Big" | "bigg"
+            | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" => MathNode::Row(vec![]),
+            "color" | "textcolor" => {
+                self.raw_argument();
+                self.argument()
+            }
+            other => MathNode::Identifier(other.to_string()),
+        }
+    }
+
+    fn styled(&mut self, variant: MathVariant) -> MathNode {
+        MathNode::Styled(variant, Box::new(self.argument()))
+    }
+
+    fn accent(&mut self, mark: &str, under: bool) -> MathNode {
+        MathNode::Accent {
+            base: Box::new(self.argument()),
+            mark: mark.into(),
+            under,
+        }
+    }
+
+    /// The delimiter following `\left` or `\right`; "." is the empty delimiter
+    fn delimiter(&mut self) -> String {
+        match self.next() {
+            Some(Token::Char(c)) if c == "." => String::new(),
+            Some(Token::Char(c)) => c,
+            Some(Token::Command(command)) => OPERATORS
+                .iter()
+                .find(|(name, _)| *name == command)
+                .map(|(_, symbol)| symbol.to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    /// Matrix-like environments, e.g., `\begin{pmatrix} a & b \\ c & d \end{pmatrix}`
+    fn environment(&mut self) -> MathNode {
+        let name = self.raw_argument();
+        if name == "array" {
+            // the column specification isn't needed
+            self.raw_argument();
+        }
+        let mut rows = vec![];
+        let mut cells = vec![];
+        loop {
+            let cell = self.sequence(&[Token::Align, Token::NewRow]);
+            cells.push(row(cell));
+            match self.next() {
+                Some(Token::Align) => {}
+                Some(Token::NewRow) => rows.push(std::mem::take(&mut cells)),
+                Some(Token::Command(command)) if command == "end" => {
+                    self.raw_argument();
+                    break;
+                }
+                // a stray closing brace or the end of input
+                Some(_) => continue,
+                None => break,
+            }
+        }
+        if !(cells.len() == 1 && cells[0] == MathNode::Row(vec![])) {
+            rows.push(cells);
+        }
+        let matrix = MathNode::Matrix(rows);
+        let (open, close) = match name.trim_end_matches('*') {
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("‖", "‖"),
+            "cases" => ("{", ""),
+            _ => return matrix,
+        };
+        MathNode::Fenced(open.into(), Box::new(matrix), close.into())
+    }
+}
+
+fn raw_token(token: &Token) -> String {
+    match token {
+        Token::Command(command) if command.chars().all(|c| c.is_ascii_alphabetic()) => {
+            format!("\\{}", command)
+        }
+        Token::Command(command) => command.clone(),
+        Token::Number(value) | Token::Letter(value) | Token::Char(value) => value.clone(),
+        Token::Open => "{".into(),
+        Token::Close => "}".into(),
+        Token::Sub => "_".into(),
+        Token::Sup => "^".into(),
+        Token::Align => "&".into(),
+        Token::NewRow => "\\\\".into(),
+        Token::Whitespace => " ".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ident(value: &str) -> MathNode {
+        MathNode::Identifier(value.into())
+    }
+
+    fn num(value: &str) -> MathNode {
+        MathNode::Number(value.into())
+    }
+
+    fn op(value: &str) -> MathNode {
+        MathNode::Operator(value.into())
+    }
+
+    #[test]
+    fn fraction_and_root() {
+        assert_eq!(
+            parse(r"\frac{1}{\sqrt[3]{x}}"),
+            MathNode::Fraction(
+                Box::new(num("1")),
+                Box::new(MathNode::Root(Box::new(ident("x")), Box::new(num("3"))))
+            )
+        )
+    }
+
+    #[test]
+    fn scripts_and_primes() {
+        assert_eq!(
+            parse("x_{i}^2"),
+            MathNode::SubSup(
+                Box::new(ident("x")),
+                Box::new(ident("i")),
+                Box::new(num("2"))
+            )
+        );
+        assert_eq!(
+            parse("f''"),
+            MathNode::Sup(Box::new(ident("f")), Box::new(op("′′")))
+        )
+    }
+
+    #[test]
+    fn left_right() {
+        assert_eq!(
+            parse(r"\left\{ x \right."),
+            MathNode::Fenced("{".into(), Box::new(ident("x")), "".into())
+        )
+    }
+
+    #[test]
+    fn environments() {
+        assert_eq!(
+            parse(r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}"),
+            MathNode::Fenced(
+                "(".into(),
+                Box::new(MathNode::Matrix(vec![
+                    vec![ident("a"), ident("b")],
+                    vec![ident("c"), ident("d")]
+                ])),
+                ")".into()
+            )
+        )
+    }
+
+    #[test]
+    fn text_and_fonts() {
+        assert_eq!(
+            parse(r"\text{if } \mathbb{R}"),
+            MathNode::Row(vec![
+                MathNode::Text("if ".into()),
+                MathNode::Styled(MathVariant::DoubleStruck, Box::new(ident("R")))
+            ])
+        )
+    }
+}
@@ -0,0 +1,187 @@
+//! Renders [`MathNode`]s as presentation MathML, which modern browsers display natively
+use super::{MathNode, MathVariant};
+
+static MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Renders a `<math>` element; `display` renders a block equation
+pub fn render(node: &MathNode, display: bool) -> String {
+    let mut mathml = format!("<math xmlns=\"{}\"", MATHML_NAMESPACE);
+    if display {
+        mathml.push_str(" display=\"block\"");
+    }
+    mathml.push('>');
+    write_node(&mut mathml, node, None);
+    mathml.push_str("</math>");
+    mathml
+}
+
+fn variant_name(variant: MathVariant) -> &'static str {
+    match variant {
+        MathVariant::Normal => "normal",
+        MathVariant::Bold => "bold",
+        MathVariant::Italic => "italic",
+        MathVariant::DoubleStruck => "double-struck",
+        MathVariant::Script => "script",
+        MathVariant::Fraktur => "fraktur",
+        MathVariant::SansSerif => "sans-serif",
+        MathVariant::Monospace => "monospace",
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn write_token(mathml: &mut String, tag: &str, value: &str, variant: Option<MathVariant>) {
+    mathml.push('<');
+    mathml.push_str(tag);
+    if let Some(variant) = variant {
+        mathml.push_str(&format!(" mathvariant=\"{}\"", variant_name(variant)));
+    }
+    mathml.push('>');
+    mathml.push_str(&escape(value));
+    mathml.push_str(&format!("</{}>", tag));
+}
+
+fn write_element(
+    mathml: &mut String,
+    tag: &str,
+    attributes: &str,
+    children: &[&MathNode],
+    variant: Option<MathVariant>,
+) {
+    mathml.push_str(&format!("<{}{}>", tag, attributes));
+    for child in children {
+        write_node(mathml, child, variant);
+    }
+    mathml.push_str(&format!("</{}>", tag));
+}
+
+fn write_node(mathml: &mut String, node: &MathNode, variant: Option<MathVariant>) {
+    match node {
+        MathNode::Identifier(value) => write_token(mathml, "mi", value, variant),
+        MathNode::Number(value) => write_token(mathml, "mn", value, variant),
+        MathNode::Operator(value) => write_token(mathml, "mo", value, None),
+        MathNode::Text(value) => write_token(mathml, "mtext", value, variant),
+        MathNode::Space(width) => mathml.push_str(&format!("<mspace width=\"{}em\"/>", width)),
+        MathNode::Row(nodes) => {
+            mathml.push_str("<mrow>");
+            for child in nodes {
+                write_node(mathml, child, variant);
+            }
+            mathml.push_str("</mrow>");
+        }
+        MathNode::Fraction(numerator, denominator) => {
+            write_element(mathml, "mfrac", "", &[numerator, denominator], variant)
+        }
+        MathNode::Sqrt(base) => write_element(mathml, "msqrt", "", &[base], variant),
+        MathNode::Root(base, index) => write_element(mathml, "mroot", "", &[base, index], variant),
+        MathNode::Sub(base, sub) => write_element(mathml, "msub", "", &[base, sub], variant),
+        MathNode::Sup(base, sup) => write_element(mathml, "msup", "", &[base, sup], variant),
+        MathNode::SubSup(base, sub, sup) => {
+            write_element(mathml, "msubsup", "", &[base, sub, sup], variant)
+        }
+        MathNode::Under(base, under) => {
+            write_element(mathml, "munder", "", &[base, under], variant)
+        }
+        MathNode::Over(base, over) => write_element(mathml, "mover", "", &[base, over], variant),
+        MathNode::UnderOver(base, under, over) => {
+            write_element(mathml, "munderover", "", &[base, under, over], variant)
+        }
+        MathNode::Accent { base, mark, under } => {
+            let (tag, attribute) = if *under {
+                ("munder", " accentunder=\"true\"")
+            } else {
+                ("mover", " accent=\"true\"")
+            };
+            mathml.push_str(&format!("<{}{}>", tag, attribute));
+            write_node(mathml, base, variant);
+            write_token(mathml, "mo", mark, None);
+            mathml.push_str(&format!("</{}>", tag));
+        }
+        MathNode::Fenced(open, body, close) => {
+            mathml.push_str("<mrow>");
+            if !open.is_empty() {
+                write_token(mathml, "mo", open, None);
+            }
+            write_node(mathml, body, variant);
+            if !close.is_empty() {
+                write_token(mathml, "mo", close, None);
+            }
+            mathml.push_str("</mrow>");
+        }
+        MathNode::Matrix(rows) => {
+            mathml.push_str("<mtable>");
+            for row in rows {
+                mathml.push_str("<mtr>");
+                for cell in row {
+                    write_element(mathml, "mtd", "", &[cell], variant);
+                }
+                mathml.push_str("</mtr>");
+            }
+            mathml.push_str("</mtable>");
+        }
+        MathNode::Styled(variant, body) => write_node(mathml, body, Some(*variant)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stem::{Notation, to_mathml};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::inline(
+        "x^2",
+        Notation::AsciiMath,
+        false,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><msup><mi>x</mi><mn>2</mn></msup></math>"
+    )]
+    #[case::display(
+        "a/b",
+        Notation::AsciiMath,
+        true,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><mfrac><mi>a</mi><mi>b</mi></mfrac></math>"
+    )]
+    #[case::escaped(
+        "x < y",
+        Notation::LatexMath,
+        false,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow><mi>x</mi><mo>&lt;</mo><mi>y</mi></mrow></math>"
+    )]
+    #[case::styled(
+        r"\mathbb{R}",
+        Notation::LatexMath,
+        false,
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi mathvariant=\"double-struck\">R</mi></math>"
+    )]
+    fn renders_mathml(
+        #[case] source: &str,
+        #[case] notation: Notation,
+        #[case] display: bool,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(to_mathml(source, notation, display), expected)
+    }
+
+    #[test]
+    fn accents_and_fences() {
+        let node = MathNode::Fenced(
+            "(".into(),
+            Box::new(MathNode::Accent {
+                base: Box::new(MathNode::Identifier("v".into())),
+                mark: "→".into(),
+                under: false,
+            }),
+            ")".into(),
+        );
+        assert_eq!(
+            render(&node, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow><mo>(</mo><mover accent=\"true\"><mi>v</mi><mo>→</mo></mover><mo>)</mo></mrow></math>"
+        )
+    }
+}
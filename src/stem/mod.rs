@@ -0,0 +1,169 @@
+//! STEM (math) support: converts AsciiMath and a practical subset of LaTeX math into MathML (for
+//! HTML-based backends) and Office Math Markup (OMML, for docx), without requiring any JavaScript
+//! or external tooling.
+//!
+//! Both notations are parsed into the same small [`MathNode`] tree, which is then rendered by the
+//! appropriate writer. Unknown input is never an error; it degrades to identifiers and operators.
+pub mod asciimath;
+pub mod latex;
+pub mod mathml;
+pub mod omml;
+
+/// The notation a STEM expression is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    AsciiMath,
+    LatexMath,
+}
+
+impl Notation {
+    /// Returns the notation given an interpreter name, e.g., the value of the `:stem:` document
+    /// attribute, or a macro or block style name ("asciimath", "latexmath", etc.)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "" | "asciimath" => Some(Notation::AsciiMath),
+            "latexmath" | "latex" | "tex" => Some(Notation::LatexMath),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Notation::AsciiMath => "asciimath",
+            Notation::LatexMath => "latexmath",
+        }
+    }
+}
+
+/// Math typeface variants, e.g., from `bb` or `\mathbf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathVariant {
+    Normal,
+    Bold,
+    Italic,
+    DoubleStruck,
+    Script,
+    Fraktur,
+    SansSerif,
+    Monospace,
+}
+
+/// A parsed math expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathNode {
+    Identifier(String),
+    Number(String),
+    Operator(String),
+    Text(String),
+    /// Horizontal space, with its width in ems
+    Space(f32),
+    Row(Vec<MathNode>),
+    Fraction(Box<MathNode>, Box<MathNode>),
+    Sqrt(Box<MathNode>),
+    /// Base, index
+    Root(Box<MathNode>, Box<MathNode>),
+    Sub(Box<MathNode>, Box<MathNode>),
+    Sup(Box<MathNode>, Box<MathNode>),
+    /// Base, subscript, superscript
+    SubSup(Box<MathNode>, Box<MathNode>, Box<MathNode>),
+    Under(Box<MathNode>, Box<MathNode>),
+    Over(Box<MathNode>, Box<MathNode>),
+    /// Base, under, over
+    UnderOver(Box<MathNode>, Box<MathNode>, Box<MathNode>),
+    /// A mark placed over (or under) its base, e.g., a hat or an underline
+    Accent {
+        base: Box<MathNode>,
+        mark: String,
+        under: bool,
+    },
+    /// Opening fence, contents, closing fence; fences may be empty (invisible)
+    Fenced(String, Box<MathNode>, String),
+    Matrix(Vec<Vec<MathNode>>),
+    Styled(MathVariant, Box<MathNode>),
+}
+
+/// Parses a STEM expression in the given notation
+pub fn parse(source: &str, notation: Notation) -> MathNode {
+    match notation {
+        Notation::AsciiMath => asciimath::parse(source),
+        Notation::LatexMath => latex::parse(source),
+    }
+}
+
+/// Converts a STEM expression to a MathML `<math>` element; `display` produces a block equation
+pub fn to_mathml(source: &str, notation: Notation, display: bool) -> String {
+    mathml::render(&parse(source, notation), display)
+}
+
+/// Converts a STEM expression to an OMML `<m:oMath>` element, wrapped in an `<m:oMathPara>` if
+/// `display` is set
+pub fn to_omml(source: &str, notation: Notation, display: bool) -> String {
+    omml::render(&parse(source, notation), display)
+}
+
+/// Collapses a list of nodes into a single node, avoiding needless single-item rows
+fn row(mut nodes: Vec<MathNode>) -> MathNode {
+    if nodes.len() == 1 {
+        nodes.remove(0)
+    } else {
+        MathNode::Row(nodes)
+    }
+}
+
+/// Whether scripts on the given base are placed underneath and above it (e.g., summation or
+/// "lim") instead of to its side
+fn takes_limits(base: &MathNode) -> bool {
+    match base {
+        MathNode::Operator(op) => {
+            ["∑", "∏", "∐", "⋀", "⋁", "⋂", "⋃", "⨁", "⨂"].contains(&op.as_str())
+        }
+        MathNode::Identifier(id) => {
+            ["lim", "Lim", "max", "min", "sup", "inf"].contains(&id.as_str())
+        }
+        _ => false,
+    }
+}
+
+/// Attaches optional sub- and superscripts to a base, as limits if the base calls for it
+fn scripted(base: MathNode, sub: Option<MathNode>, sup: Option<MathNode>) -> MathNode {
+    let limits = takes_limits(&base);
+    match (sub, sup) {
+        (None, None) => base,
+        (Some(sub), None) if limits => MathNode::Under(Box::new(base), Box::new(sub)),
+        (Some(sub), None) => MathNode::Sub(Box::new(base), Box::new(sub)),
+        (None, Some(sup)) if limits => MathNode::Over(Box::new(base), Box::new(sup)),
+        (None, Some(sup)) => MathNode::Sup(Box::new(base), Box::new(sup)),
+        (Some(sub), Some(sup)) if limits => {
+            MathNode::UnderOver(Box::new(base), Box::new(sub), Box::new(sup))
+        }
+        (Some(sub), Some(sup)) => MathNode::SubSup(Box::new(base), Box::new(sub), Box::new(sup)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", Some(Notation::AsciiMath))]
+    #[case("asciimath", Some(Notation::AsciiMath))]
+    #[case("latexmath", Some(Notation::LatexMath))]
+    #[case("tex", Some(Notation::LatexMath))]
+    #[case("mathml", None)]
+    fn notation_from_name(#[case] name: &str, #[case] expected: Option<Notation>) {
+        assert_eq!(Notation::from_name(name), expected)
+    }
+
+    #[test]
+    fn both_notations_agree() {
+        assert_eq!(
+            to_mathml("sqrt(x^2+1)", Notation::AsciiMath, false),
+            to_mathml(r"\sqrt{x^2+1}", Notation::LatexMath, false)
+        );
+        assert_eq!(
+            to_mathml("sum_(i=1)^n i", Notation::AsciiMath, true),
+            to_mathml(r"\sum_{i=1}^n i", Notation::LatexMath, true)
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Renders [`MathNode`]s as Office Math Markup Language (OMML), the equation format used by Word
+use super::{MathNode, MathVariant};
+
+pub static OMML_NAMESPACE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/math";
+
+/// N-ary operators, which take the following expression as their operand
+static NARY_OPERATORS: &[&str] = &[
+    "∑", "∏", "∐", "⋀", "⋁", "⋂", "⋃", "⨁", "⨂", "∫", "∬", "∭", "∮",
+];
+
+/// Renders an `<m:oMath>` element, wrapped in an `<m:oMathPara>` if `display` is set. The `m`
+/// namespace prefix must be declared by the enclosing document.
+pub fn render(node: &MathNode, display: bool) -> String {
+    let mut omml = String::from("<m:oMath>");
+    write_nodes(&mut omml, std::slice::from_ref(node), None);
+    omml.push_str("</m:oMath>");
+    if display {
+        format!("<m:oMathPara>{}</m:oMathPara>", omml)
+    } else {
+        omml
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The run properties for a variant, i.e., the script and style
+fn variant_properties(variant: MathVariant) -> &'static str {
+    match variant {
+        MathVariant::Normal => "<m:sty m:val=\"p\"/>",
+        MathVariant::Bold => "<m:sty m:val=\"b\"/>",
+        MathVariant::Italic => "<m:sty m:val=\"i\"/>",
+        MathVariant::DoubleStruck => "<m:scr m:val=\"double-struck\"/><m:sty m:val=\"p\"/>",
+        MathVariant::Script => "<m:scr m:val=\"script\"/><m:sty m:val=\"p\"/>",
+        MathVariant::Fraktur => "<m:scr m:val=\"fraktur\"/><m:sty m:val=\"p\"/>",
+        MathVariant::SansSerif => "<m:scr m:val=\"sans-serif\"/>",
+        MathVariant::Monospace => "<m:scr m:val=\"monospace\"/><m:sty m:val=\"p\"/>",
+    }
+}
+
+fn write_run(omml: &mut String, value: &str, properties: &str) {
+    omml.push_str("<m:r>");
+    if !properties.is_empty() {
+        omml.push_str(&format!("<m:rPr>{}</m:rPr>", properties));
+    }
+    omml.push_str(&format!(
+        "<m:t xml:space=\"preserve\">{}</m:t></m:r>",
+        escape(value)
+    ));
+}
+
+/// Writes an argument element, e.g., `<m:num>...</m:num>`
+fn write_argument(omml: &mut String, tag: &str, node: &MathNode, variant: Option<MathVariant>) {
+    omml.push_str(&format!("<m:{}>", tag));
+    write_nodes(omml, std::slice::from_ref(node), variant);
+    omml.push_str(&format!("</m:{}>", tag));
+}
+
+/// If the node is an n-ary operator, possibly with limits, returns the operator, its limits, and
+/// whether they're placed under and over (rather than beside) it
+fn as_nary(node: &MathNode) -> Option<(&str, Option<&MathNode>, Option<&MathNode>, bool)> {
+    let (base, sub, sup, limits) = match node {
+        MathNode::Operator(_) => (node, None, None, false),
+        MathNode::Sub(base, sub) => (base.as_ref(), Some(sub.as_ref()), None, false),
+        MathNode::Under(base, sub) => (base.as_ref(), Some(sub.as_ref()), None, true),
+        MathNode::Sup(base, sup) => (base.as_ref(), None, Some(sup.as_ref()), false),
+        MathNode::Over(base, sup) => (base.as_ref(), None, Some(sup.as_ref()), true),
+        MathNode::SubSup(base, sub, sup) => {
+            (base.as_ref(), Some(sub.as_ref()), Some(sup.as_ref()), false)
+        }
+        MathNode::UnderOver(base, sub, sup) => {
+            (base.as_ref(), Some(sub.as_ref()), Some(sup.as_ref()), true)
+        }
+        _ => return None,
+    };
+    match base {
+        MathNode::Operator(op) if NARY_OPERATORS.contains(&op.as_str()) => {
+            Some((op.as_str(), sub, sup, limits))
+        }
+        _ => None,
+    }
+}
+
+/// Writes a sequence of nodes; n-ary operators take the node that follows as their operand
+fn write_nodes(omml: &mut String, nodes: &[MathNode], variant: Option<MathVariant>) {
+    let mut idx = 0;
+    while idx < nodes.len() {
+        let node = &nodes[idx];
+        if let Some((operator, sub, sup, limits)) = as_nary(node) {
+            omml.push_str("<m:nary><m:naryPr>");
+            omml.push_str(&format!("<m:chr m:val=\"{}\"/>", operator));
+            omml.push_str(&format!(
+                "<m:limLoc m:val=\"{}\"/>",
+                if limits { "undOvr" } else { "subSup" }
+            ));
+            if sub.is_none() {
+                omml.push_str("<m:subHide m:val=\"1\"/>");
+            }
+            if sup.is_none() {
+                omml.push_str("<m:supHide m:val=\"1\"/>");
+            }
+            omml.push_str("</m:naryPr>");
+            write_argument(omml, "sub", sub.unwrap_or(&MathNode::Row(vec![])), variant);
+            write_argument(omml, "sup", sup.unwrap_or(&MathNode::Row(vec![])), variant);
+            omml.push_str("<m:e>");
+            if let Some(operand) = nodes.get(idx + 1) {
+                write_nodes(omml, std::slice::from_ref(operand), variant);
+                idx += 1;
+            }
+            omml.push_str("</m:e></m:nary>");
+        } else {
+            write_node(omml, node, variant);
+        }
+        idx += 1;
+    }
+}
+
+fn write_node(omml: &mut String, node: &MathNode, variant: Option<MathVariant>) {
+    match node {
+        MathNode::Identifier(value) => {
+            let properties = match variant {
+                Some(variant) => variant_properties(variant),
+                // multi-letter identifiers, e.g., function names, are upright
+                None if value.chars().count() > 1 => variant_properties(MathVariant::Normal),
+                None => "",
+            };
+            write_run(omml, value, properties)
+        }
+        MathNode::Number(value) => {
+            write_run(omml, value, variant.map(variant_properties).unwrap_or(""))
+        }
+        MathNode::Operator(value) => write_run(omml, value, ""),
+        MathNode::Text(value) => write_run(omml, value, "<m:nor/>"),
+        MathNode::Space(width) => {
+            // approximated with regular and em spaces
+            let spaces = if *width >= 1.0 {
+                "\u{2003}".repeat(width.round() as usize)
+            } else {
+                String::from(" ")
+            };
+            write_run(omml, &spaces, "")
+        }
+        MathNode::Row(nodes) => write_nodes(omml, nodes, variant),
+        MathNode::Fraction(numerator, denominator) => {
+            omml.push_str("<m:f>");
+            write_argument(omml, "num", numerator, variant);
+            write_argument(omml, "den", denominator, variant);
+            omml.push_str("</m:f>");
+        }
+        MathNode::Sqrt(base) => {
+            omml.push_str("<m:rad><m:radPr><m:degHide m:val=\"1\"/></m:radPr><m:deg/>");
+            write_argument(omml, "e", base, variant);
+            omml.push_str("</m:rad>");
+        }
+        MathNode::Root(base, index) => {
+            omml.push_str("<m:rad>");
+            write_argument(omml, "deg", index, variant);
+            write_argument(omml, "e", base, variant);
+            omml.push_str("</m:rad>");
+        }
+        MathNode::Sub(base, sub) => {
+            omml.push_str("<m:sSub>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "sub", sub, variant);
+            omml.push_str("</m:sSub>");
+        }
+        MathNode::Sup(base, sup) => {
+            omml.push_str("<m:sSup>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "sup", sup, variant);
+            omml.push_str("</m:sSup>");
+        }
+        MathNode::SubSup(base, sub, sup) => {
+            omml.push_str("<m:sSubSup>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "sub", sub, variant);
+            write_argument(omml, "sup", sup, variant);
+            omml.push_str("</m:sSubSup>");
+        }
+        MathNode::Under(base, under) => {
+            omml.push_str("<m:limLow>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "lim", under, variant);
+            omml.push_str("</m:limLow>");
+        }
+        MathNode::Over(base, over) => {
+            omml.push_str("<m:limUpp>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "lim", over, variant);
+            omml.push_str("</m:limUpp>");
+        }
+        MathNode::UnderOver(base, under, over) => {
+            omml.push_str("<m:limUpp><m:e><m:limLow>");
+            write_argument(omml, "e", base, variant);
+            write_argument(omml, "lim", under, variant);
+            omml.push_str("</m:limLow></m:e>");
+            write_argument(omml, "lim", over, variant);
+            omml.push_str("</m:limUpp>");
+        }
+        MathNode::Accent { base, mark, under } => match (mark.as_str(), under) {
+            ("¯", false) | ("_", true) => {
+                omml.push_str(&format!(
+                    "<m:bar><m:barPr><m:pos m:val=\"{}\"/></m:barPr>",
+                    if *under { "bot" } else { "top" }
+                ));
+                write_argument(omml, "e", base, variant);
+                omml.push_str("</m:bar>");
+            }
+            (_, true) | ("⏞", false) => {
+                omml.push_str(&format!(
+                    "<m:groupChr><m:groupChrPr><m:chr m:val=\"{}\"/><m:pos m:val=\"{}\"/></m:groupChrPr>",
+                    escape(mark),
+                    if *under { "bot" } else { "top" }
+                ));
+                write_argument(omml, "e", base, variant);
+                omml.push_str("</m:groupChr>");
+            }
+            (mark, false) => {
+                let combining = match mark {
+                    "^" => "\u{0302}",
+                    "~" => "\u{0303}",
+                    "→" => "\u{20D7}",
+                    "." => "\u{0307}",
+                    ".." => "\u{0308}",
+                    other => other,
+                };
+                omml.push_str(&format!(
+                    "<m:acc><m:accPr><m:chr m:val=\"{}\"/></m:accPr>",
+                    escape(combining)
+                ));
+                write_argument(omml, "e", base, variant);
+                omml.push_str("</m:acc>");
+            }
+        },
+        MathNode::Fenced(open, body, close) => {
+            omml.push_str(&format!(
+                "<m:d><m:dPr><m:begChr m:val=\"{}\"/><m:endChr m:val=\"{}\"/></m:dPr>",
+                escape(open),
+                escape(close)
+            ));
+            write_argument(omml, "e", body, variant);
+            omml.push_str("</m:d>");
+        }
+        MathNode::Matrix(rows) => {
+            omml.push_str("<m:m>");
+            for row in rows {
+                omml.push_str("<m:mr>");
+                for cell in row {
+                    write_argument(omml, "e", cell, variant);
+                }
+                omml.push_str("</m:mr>");
+            }
+            omml.push_str("</m:m>");
+        }
+        MathNode::Styled(variant, body) => write_node(omml, body, Some(*variant)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stem::{Notation, to_omml};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::fraction(
+        "1/2",
+        false,
+        "<m:oMath><m:f><m:num><m:r><m:t xml:space=\"preserve\">1</m:t></m:r></m:num><m:den><m:r><m:t xml:space=\"preserve\">2</m:t></m:r></m:den></m:f></m:oMath>"
+    )]
+    #[case::display_sqrt(
+        "sqrt x",
+        true,
+        "<m:oMathPara><m:oMath><m:rad><m:radPr><m:degHide m:val=\"1\"/></m:radPr><m:deg/><m:e><m:r><m:t xml:space=\"preserve\">x</m:t></m:r></m:e></m:rad></m:oMath></m:oMathPara>"
+    )]
+    #[case::nary(
+        "sum_i i",
+        false,
+        "<m:oMath><m:nary><m:naryPr><m:chr m:val=\"∑\"/><m:limLoc m:val=\"undOvr\"/><m:supHide m:val=\"1\"/></m:naryPr><m:sub><m:r><m:t xml:space=\"preserve\">i</m:t></m:r></m:sub><m:sup></m:sup><m:e><m:r><m:t xml:space=\"preserve\">i</m:t></m:r></m:e></m:nary></m:oMath>"
+    )]
+    fn renders_omml(#[case] source: &str, #[case] display: bool, #[case] expected: &str) {
+        assert_eq!(to_omml(source, Notation::AsciiMath, display), expected)
+    }
+}
@@ -37,6 +37,9 @@ pub fn target_and_attrs_from_token(token: &Token) -> (String, Option<ElementMeta
         TokenType::BlockImageMacro => {
             token.text()[7..].to_string() // after image::
         }
+        TokenType::BlockAudioMacro | TokenType::BlockVideoMacro => {
+            token.text()[7..].to_string() // after audio:: or video::
+        }
         TokenType::InlineImageMacro => {
             token.text()[6..].to_string() // after image:
         }
@@ -65,6 +68,51 @@ pub fn target_and_attrs_from_token(token: &Token) -> (String, Option<ElementMeta
     (target, metadata)
 }
 
+/// Percent-encodes a value for use in a URL (e.g., the subject of a `mailto:` link), leaving only
+/// unreserved characters as they are
+pub fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// The indentation (in spaces and tabs) of a line, or `None` if the line is blank; other
+/// whitespace, e.g., a no-break space, is content
+pub fn line_indentation(line: &str) -> Option<usize> {
+    match line.trim().is_empty() {
+        true => None,
+        false => Some(line.len() - line.trim_start_matches([' ', '\t']).len()),
+    }
+}
+
+/// Replaces up to `strip` leading spaces and tabs of a line with `indent` spaces; blank lines
+/// are emptied
+pub fn reindent_line(line: &str, strip: usize, indent: usize) -> String {
+    match line.trim().is_empty() {
+        true => String::new(),
+        false => {
+            let indentation = line.len() - line.trim_start_matches([' ', '\t']).len();
+            format!("{}{}", " ".repeat(indent), &line[strip.min(indentation)..])
+        }
+    }
+}
+
+/// Strips the common indentation from the lines of the given text, then indents each line by
+/// `indent` spaces, as with the `indent=N` attribute
+pub fn reindent(text: &str, indent: usize) -> String {
+    let strip = text.lines().filter_map(line_indentation).min().unwrap_or(0);
+    text.split('\n')
+        .map(|line| reindent_line(line, strip, indent))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Extracts included page ranges from the "lines=" attribute of an include directive
 pub fn extract_page_ranges(ranges_str: &str) -> Vec<i32> {
     let mut ranges: Vec<i32> = vec![];
@@ -98,15 +146,41 @@ pub fn extract_page_ranges(ranges_str: &str) -> Vec<i32> {
     ranges
 }
 
+/// Extracts the line numbers from the "highlight=" attribute of a source block, e.g., "2..4,7"
+/// or "2-4;7", sorted and deduplicated; ranges are cut off at `last`, the block's last line
+pub fn extract_line_numbers(numbers_str: &str, last: usize) -> Vec<usize> {
+    let mut numbers: Vec<usize> = vec![];
+    for part in numbers_str
+        .split([',', ';', ' '])
+        .filter(|part| !part.is_empty())
+    {
+        let (start, end) = part
+            .split_once("..")
+            .or_else(|| part.split_once('-'))
+            .unwrap_or((part, part));
+        if let (Ok(start), Ok(end)) = (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+            numbers.extend(start..=end.min(last))
+        }
+    }
+    numbers.sort();
+    numbers.dedup();
+    numbers
+}
+
 pub fn extract_attributes(attribute_list: &str) -> Vec<String> {
-    let mut attributes: Vec<String> = vec![];
+    let mut quoted_attributes: Vec<String> = vec![];
     let mut non_quoted_key_values = attribute_list.to_owned();
     // TK "1,2,4" should be a single attribute, not "1,", 2, 4"
     for quoted_attr in RE_NAMED_QUOTED.captures_iter(attribute_list) {
         let (total, [_]) = quoted_attr.extract();
-        attributes.push(total.to_owned());
+        quoted_attributes.push(total.to_owned());
         non_quoted_key_values = non_quoted_key_values.replace(total, "");
     }
-    attributes.extend(non_quoted_key_values.split(',').map(|s| s.to_string()));
+    // the named, quoted attributes go last so as not to shift the positional ones
+    let mut attributes: Vec<String> = non_quoted_key_values
+        .split(',')
+        .map(|s| s.to_string())
+        .collect();
+    attributes.extend(quoted_attributes);
     attributes
 }
@@ -0,0 +1,3 @@
+class Cat:
+    def meow(self):
+        return "meow"
@@ -10,8 +10,85 @@ mod docx_tests {
     use asciidocr::backends::docx::render_docx;
     use asciidocr::parser::Parser;
     use asciidocr::scanner::Scanner;
+    use docx_rs::{DocumentChild, read_docx};
     use tempfile::NamedTempFile;
 
+    /// Renders the asciidoc to a docx file, returning the style and text of each paragraph
+    fn rendered_paragraphs(adoc_str: &str) -> Vec<(Option<String>, String)> {
+        let parsed_asg = Parser::new_no_target_resolution(PathBuf::from("tests/data/"))
+            .parse(Scanner::new(adoc_str))
+            .expect("Failed to parse document");
+        let temp_docx = NamedTempFile::new().unwrap();
+        render_docx(&parsed_asg, temp_docx.path()).expect("Failed to render docx");
+        let docx = read_docx(&fs::read(temp_docx.path()).unwrap()).expect("Failed to read docx");
+        docx.document
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                DocumentChild::Paragraph(para) => Some((
+                    para.property.style.as_ref().map(|style| style.val.clone()),
+                    para.raw_text(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_captioned_example() {
+        let paragraphs = rendered_paragraphs(
+            ".A captioned example\n====\nInside the example.\n====\n\nAfter.\n",
+        );
+        assert_eq!(
+            paragraphs,
+            vec![
+                (
+                    Some("Caption".into()),
+                    "Example 1. A captioned example".into()
+                ),
+                (Some("Example".into()), "Inside the example.".into()),
+                (Some("Normal".into()), "After.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quote_attribution() {
+        let paragraphs =
+            rendered_paragraphs("[quote, Ann, Some Book]\n____\nQuoted text.\n____\n\nAfter.\n");
+        assert_eq!(
+            paragraphs,
+            vec![
+                (Some("Quote".into()), "Quoted text.".into()),
+                (Some("Quote".into()), "— Ann, Some Book".into()),
+                (Some("Normal".into()), "After.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sidebar_style() {
+        let paragraphs =
+            rendered_paragraphs(".A sidebar\n****\nInside the sidebar.\n****\n\nAfter.\n");
+        assert_eq!(
+            paragraphs,
+            vec![
+                (Some("Caption".into()), "A sidebar".into()),
+                (Some("Sidebar".into()), "Inside the sidebar.".into()),
+                (Some("Normal".into()), "After.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_passthrough_text_is_raw() {
+        let paragraphs = rendered_paragraphs("A +<b>+ and pass:c[a & b] here.\n");
+        assert_eq!(
+            paragraphs,
+            vec![(Some("Normal".into()), "A <b> and a & b here.".into())]
+        );
+    }
+
     #[test]
     fn first_test_for_sanity() {
         let test_dir = PathBuf::from("tests/data/");
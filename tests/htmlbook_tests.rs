@@ -74,6 +74,30 @@ fn footnotes() {
     assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
 }
 
+#[test]
+fn named_footnotes() {
+    let fn_pattern = String::from("htmlbook/footnote-named");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn unterminated_footnotes() {
+    let fn_pattern = String::from("htmlbook/footnote-unterminated");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn undefined_footnotes() {
+    let fn_pattern = String::from("htmlbook/footnote-undefined");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
 #[test]
 fn literal_blocks() {
     let fn_pattern = String::from("htmlbook/literal");
@@ -82,6 +106,14 @@ fn literal_blocks() {
     assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
 }
 
+#[test]
+fn literal_multibyte_whitespace() {
+    let fn_pattern = String::from("htmlbook/literal-multibyte-whitespace");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
 #[test]
 fn literals_straight_quotes() {
     let fn_pattern = String::from("htmlbook/literals-straight-squo");
@@ -98,6 +130,38 @@ fn passthroughs() {
     assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
 }
 
+#[test]
+fn inline_passthroughs() {
+    let fn_pattern = String::from("htmlbook/passthroughs-inline");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn unterminated_passthroughs() {
+    let fn_pattern = String::from("htmlbook/passthroughs-unterminated");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn backslash_escapes() {
+    let fn_pattern = String::from("htmlbook/backslash-escapes");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn smart_quotes() {
+    let fn_pattern = String::from("htmlbook/smart-quotes");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
 #[test]
 fn super_and_subscripts() {
     let fn_pattern = String::from("htmlbook/super-subscript");
@@ -221,3 +285,267 @@ fn links() {
     let html_fn = format!("{}.html", fn_pattern);
     assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
 }
+
+#[test]
+fn section_numbers() {
+    let fn_pattern = String::from("htmlbook/section-numbers");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn section_numbers_book_parts() {
+    let fn_pattern = String::from("htmlbook/section-numbers-book");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn block_caption_numbers() {
+    let fn_pattern = String::from("htmlbook/captions");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn localized_labels() {
+    let fn_pattern = String::from("htmlbook/admonitions-localized");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn inline_anchors() {
+    let fn_pattern = String::from("htmlbook/inline-anchors");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn inline_anchors_text() {
+    let fn_pattern = String::from("htmlbook/inline-anchors-text");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn cross_references_to_other_documents() {
+    let fn_pattern = String::from("htmlbook/cross-ref-documents");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn email_links() {
+    let fn_pattern = String::from("htmlbook/email-links");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn email_links_in_spans() {
+    let fn_pattern = String::from("htmlbook/email-links-in-spans");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn ui_macros() {
+    let fn_pattern = String::from("htmlbook/ui-macros");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn ui_macros_in_spans() {
+    let fn_pattern = String::from("htmlbook/ui-macros-in-spans");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn ui_macros_stray_marks() {
+    let fn_pattern = String::from("htmlbook/ui-macros-stray-marks");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn ui_macros_require_experimental() {
+    let fn_pattern = String::from("htmlbook/ui-macros-not-experimental");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn stem() {
+    let fn_pattern = String::from("htmlbook/stem");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn stem_latexmath_default() {
+    let fn_pattern = String::from("htmlbook/stem-latexmath");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn stem_in_spans() {
+    let fn_pattern = String::from("htmlbook/stem-in-spans");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn stem_empty() {
+    let fn_pattern = String::from("htmlbook/stem-empty");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn index() {
+    let fn_pattern = String::from("htmlbook/index");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn index_terms_in_spans() {
+    let fn_pattern = String::from("htmlbook/index-terms-in-spans");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn index_empty_terms() {
+    let fn_pattern = String::from("htmlbook/index-empty-terms");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn bibliography() {
+    let fn_pattern = String::from("htmlbook/bibliography");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn bibliography_anchors_text() {
+    let fn_pattern = String::from("htmlbook/bibliography-anchors-text");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn attributes_percent() {
+    let fn_pattern = String::from("htmlbook/attributes-percent");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn source_lines() {
+    let fn_pattern = String::from("htmlbook/source-lines");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn source_highlighting() {
+    let fn_pattern = String::from("htmlbook/source-highlighting");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn code_callouts_linked() {
+    let fn_pattern = String::from("htmlbook/code-callouts-linked");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn collapsible() {
+    let fn_pattern = String::from("htmlbook/collapsible");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn collapsible_localized() {
+    let fn_pattern = String::from("htmlbook/collapsible-localized");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn media() {
+    let fn_pattern = String::from("htmlbook/media");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn admonition_icons() {
+    let fn_pattern = String::from("htmlbook/admonition-icons");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn admonition_icons_image() {
+    let fn_pattern = String::from("htmlbook/admonition-icons-image");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn alignment() {
+    let fn_pattern = String::from("htmlbook/alignment");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
+
+#[test]
+fn open_masquerade() {
+    let fn_pattern = String::from("htmlbook/open-masquerade");
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn)
+}
@@ -134,6 +134,15 @@ fn test_footnotes() {
     assert_parsed_doc_matches_expected_asg(&adoc_fn, &asg_json_fn)
 }
 
+#[test]
+/// Passthrough text stays raw, flagged with any substitutions converters still need to apply
+fn test_passthroughs() {
+    let fn_pattern = "inlines/passthroughs";
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let asg_json_fn = format!("{}.json", fn_pattern);
+    assert_parsed_doc_matches_expected_asg(&adoc_fn, &asg_json_fn)
+}
+
 #[test]
 /// Attribute refs are replaced as a part of a pre-processing step
 fn test_attribute_ref_replacment() {
@@ -1,10 +1,16 @@
 pub mod common;
-use common::assert_parsed_doc_matches_expected_asg;
+use common::{assert_parsed_doc_matches_expected_asg, assert_rendered_htmlbook_matches_expected};
 use logtest::Logger;
 
+// logtest can only set the logger once per test binary, so the warnings are checked in one test
 #[test]
-fn test_missing_references_warns() {
+fn test_warnings() {
     let mut logger = Logger::start();
+    missing_references_warn(&mut logger);
+    undefined_footnotes_warn(&mut logger);
+}
+
+fn missing_references_warn(logger: &mut Logger) {
     let fn_pattern = "documents/references-missing";
     let adoc_fn = format!("{}.adoc", fn_pattern);
     let asg_json_fn = format!("{}.json", fn_pattern);
@@ -12,3 +18,15 @@ fn test_missing_references_warns() {
     let log_str = logger.pop().unwrap().args().to_owned();
     assert!(log_str.contains("Unable to find xref:"));
 }
+
+fn undefined_footnotes_warn(logger: &mut Logger) {
+    let fn_pattern = "htmlbook/footnote-undefined";
+    let adoc_fn = format!("{}.adoc", fn_pattern);
+    let html_fn = format!("{}.html", fn_pattern);
+    assert_rendered_htmlbook_matches_expected(&adoc_fn, &html_fn);
+    let log_str = logger.pop().unwrap().args().to_owned();
+    assert!(
+        log_str
+            .contains("Footnote reference missing has no matching footnote definition, see line 1")
+    );
+}